@@ -0,0 +1,16 @@
+/// Order in which bits are assigned, within a storage unit.
+///
+/// With `LsbFirst`, the first value occupies the least significant bits; with `MsbFirst`, the most
+/// significant ones.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitOrder {
+    LsbFirst,
+    MsbFirst,
+}
+
+impl Default for BitOrder {
+    fn default() -> Self {
+        BitOrder::LsbFirst
+    }
+}
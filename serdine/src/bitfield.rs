@@ -0,0 +1,112 @@
+//! Support for bitfield structs, i.e. structs whose fields are packed into a single storage word.
+//!
+//! Field values are converted from/to bits via the `FromBits`/`ToBits` traits, which are
+//! implemented for integers and `bool`, and derived (respectively, by `Deserialize` and `Serialize`)
+//! for enums.
+
+use crate::odd_width::WidthInteger;
+use crate::Error;
+
+pub trait FromBits: Sized {
+    /// `bits` are right-aligned; returns None if they don't represent a valid value.
+    fn from_bits(bits: u64, width: u32) -> Option<Self>;
+}
+
+pub trait ToBits {
+    /// Returns None if the value doesn't fit the width.
+    fn to_bits(&self, width: u32) -> Option<u64>;
+}
+
+macro_rules! impl_bits_for_integer {
+    ( $( $type:ty ),+ ) => {
+        $(
+            impl FromBits for $type {
+                fn from_bits(bits: u64, width: u32) -> Option<Self> {
+                    integer_from_bits(bits, width)
+                }
+            }
+
+            impl ToBits for $type {
+                fn to_bits(&self, width: u32) -> Option<u64> {
+                    integer_to_bits(self, width)
+                }
+            }
+        )+
+    };
+}
+
+impl_bits_for_integer!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl FromBits for bool {
+    fn from_bits(bits: u64, _width: u32) -> Option<Self> {
+        match bits {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl ToBits for bool {
+    fn to_bits(&self, _width: u32) -> Option<u64> {
+        Some(*self as u64)
+    }
+}
+
+fn integer_from_bits<T: WidthInteger>(bits: u64, width: u32) -> Option<T> {
+    let value = if T::SIGNED {
+        let shift = 64 - width;
+        ((bits << shift) as i64 >> shift) as i128
+    } else {
+        bits as i128
+    };
+
+    T::from_i128(value)
+}
+
+fn integer_to_bits<T: WidthInteger>(value: &T, width: u32) -> Option<u64> {
+    let value = value.to_i128();
+
+    let (min, max) = if T::SIGNED {
+        (-(1_i128 << (width - 1)), (1_i128 << (width - 1)) - 1)
+    } else {
+        (0, (1_i128 << width) - 1)
+    };
+
+    if value >= min && value <= max {
+        Some(value as u64 & mask(width))
+    } else {
+        None
+    }
+}
+
+/// Extracts a field from the storage word; used by the derived implementations.
+///
+pub fn unpack<T: FromBits>(
+    storage: u64,
+    offset: u32,
+    width: u32,
+    field: &'static str,
+) -> Result<T, std::io::Error> {
+    let bits = (storage >> offset) & mask(width);
+
+    T::from_bits(bits, width).ok_or_else(|| Error::InvalidBitfieldValue { field, bits }.into())
+}
+
+/// Places a field into the storage word position; used by the derived implementations.
+///
+pub fn pack<T: ToBits>(
+    value: &T,
+    offset: u32,
+    width: u32,
+    field: &'static str,
+) -> Result<u64, std::io::Error> {
+    match value.to_bits(width) {
+        Some(bits) if bits <= mask(width) => Ok(bits << offset),
+        _ => Err(Error::BitfieldOverflow { field, width }.into()),
+    }
+}
+
+fn mask(width: u32) -> u64 {
+    u64::MAX >> (64 - width)
+}
@@ -0,0 +1,328 @@
+//! Bit-granular de/serialization, for formats whose values are not byte aligned.
+//!
+//! With `BitOrder::MsbFirst`, bits are consumed starting from the most significant bit of each
+//! byte, and the first bit read is the most significant of a value; with `BitOrder::LsbFirst`, the
+//! opposite applies to both.
+//!
+//! Types implementing `BitDeserialize`/`BitSerialize` can be nested in byte-oriented derived
+//! structs via the `bit_order` field attribute; the bit stream is aligned to the next byte at the
+//! end of the field.
+
+use std::io::{Read, Write};
+
+use crate::bitfield::{FromBits, ToBits};
+use crate::{BitOrder, Error};
+
+const MAX_BITS: u32 = 64;
+
+// ////////////////////////////////////////////////////////////////////////////////
+// READER/WRITER
+// ////////////////////////////////////////////////////////////////////////////////
+
+fn check_bits_count(count: u32) -> Result<(), std::io::Error> {
+    if count > MAX_BITS {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Can't read/write more than {} bits at once", MAX_BITS),
+        ));
+    }
+
+    Ok(())
+}
+
+pub struct BitReader<R> {
+    inner: R,
+    bit_order: BitOrder,
+    current_byte: u8,
+    // Bits not yet consumed in the current byte.
+    available_bits: u32,
+}
+
+impl<R: Read> BitReader<R> {
+    pub fn new(inner: R, bit_order: BitOrder) -> Self {
+        Self {
+            inner,
+            bit_order,
+            current_byte: 0,
+            available_bits: 0,
+        }
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool, std::io::Error> {
+        if self.available_bits == 0 {
+            let mut buffer = [0; 1];
+            self.inner.read_exact(&mut buffer)?;
+
+            self.current_byte = buffer[0];
+            self.available_bits = 8;
+        }
+
+        let position = match self.bit_order {
+            BitOrder::MsbFirst => self.available_bits - 1,
+            BitOrder::LsbFirst => 8 - self.available_bits,
+        };
+
+        self.available_bits -= 1;
+
+        Ok(self.current_byte & (1 << position) != 0)
+    }
+
+    /// Reads up to 64 bits; the result is right-aligned. Larger counts fail with an
+    /// `InvalidInput` error.
+    ///
+    pub fn read_bits(&mut self, count: u32) -> Result<u64, std::io::Error> {
+        check_bits_count(count)?;
+
+        let mut value = 0;
+
+        for bit_index in 0..count {
+            let bit = self.read_bit()? as u64;
+
+            value = match self.bit_order {
+                BitOrder::MsbFirst => value << 1 | bit,
+                BitOrder::LsbFirst => value | bit << bit_index,
+            };
+        }
+
+        Ok(value)
+    }
+
+    /// Discards the remaining bits of the current byte, if any.
+    ///
+    pub fn align(&mut self) {
+        self.available_bits = 0;
+    }
+
+    pub fn is_aligned(&self) -> bool {
+        self.available_bits == 0
+    }
+
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+/// Partially written bytes are not written to the inner writer until the stream is aligned; use
+/// `finish()` in order to make sure that all the bits are written.
+///
+pub struct BitWriter<W> {
+    inner: W,
+    bit_order: BitOrder,
+    current_byte: u8,
+    // Bits already written in the current byte.
+    used_bits: u32,
+}
+
+impl<W: Write> BitWriter<W> {
+    pub fn new(inner: W, bit_order: BitOrder) -> Self {
+        Self {
+            inner,
+            bit_order,
+            current_byte: 0,
+            used_bits: 0,
+        }
+    }
+
+    pub fn write_bit(&mut self, bit: bool) -> Result<(), std::io::Error> {
+        let position = match self.bit_order {
+            BitOrder::MsbFirst => 7 - self.used_bits,
+            BitOrder::LsbFirst => self.used_bits,
+        };
+
+        self.current_byte |= (bit as u8) << position;
+        self.used_bits += 1;
+
+        if self.used_bits == 8 {
+            self.flush_byte()?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the `count` least significant bits of `value` (up to 64). Larger counts fail with an
+    /// `InvalidInput` error.
+    ///
+    pub fn write_bits(&mut self, value: u64, count: u32) -> Result<(), std::io::Error> {
+        check_bits_count(count)?;
+
+        for bit_index in 0..count {
+            let position = match self.bit_order {
+                BitOrder::MsbFirst => count - 1 - bit_index,
+                BitOrder::LsbFirst => bit_index,
+            };
+
+            self.write_bit(value & (1 << position) != 0)?;
+        }
+
+        Ok(())
+    }
+
+    /// Pads the current byte with zeros, if it's partially written, and writes it.
+    ///
+    pub fn align(&mut self) -> Result<(), std::io::Error> {
+        if self.used_bits > 0 {
+            self.flush_byte()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn is_aligned(&self) -> bool {
+        self.used_bits == 0
+    }
+
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    /// Aligns the stream, and returns the inner writer.
+    ///
+    pub fn finish(mut self) -> Result<W, std::io::Error> {
+        self.align()?;
+        Ok(self.inner)
+    }
+
+    fn flush_byte(&mut self) -> Result<(), std::io::Error> {
+        self.inner.write_all(&[self.current_byte])?;
+
+        self.current_byte = 0;
+        self.used_bits = 0;
+
+        Ok(())
+    }
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// TRAITS
+// ////////////////////////////////////////////////////////////////////////////////
+
+pub trait BitDeserialize: Sized {
+    fn bit_deserialize<R: Read>(r: &mut BitReader<R>) -> Result<Self, std::io::Error>;
+}
+
+pub trait BitSerialize {
+    fn bit_serialize<W: Write>(&self, w: &mut BitWriter<W>) -> Result<(), std::io::Error>;
+}
+
+macro_rules! impl_bits_traits_for_numeric {
+    ( $( $type:ty ),+ ) => {
+        $(
+            impl BitDeserialize for $type {
+                fn bit_deserialize<R: Read>(r: &mut BitReader<R>) -> Result<Self, std::io::Error> {
+                    let bits = r.read_bits(<$type>::BITS)?;
+                    Ok(bits as $type)
+                }
+            }
+
+            impl BitSerialize for $type {
+                fn bit_serialize<W: Write>(&self, w: &mut BitWriter<W>) -> Result<(), std::io::Error> {
+                    w.write_bits(*self as u64, <$type>::BITS)
+                }
+            }
+        )+
+    };
+}
+
+impl_bits_traits_for_numeric!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl BitDeserialize for bool {
+    fn bit_deserialize<R: Read>(r: &mut BitReader<R>) -> Result<Self, std::io::Error> {
+        r.read_bit()
+    }
+}
+
+impl BitSerialize for bool {
+    fn bit_serialize<W: Write>(&self, w: &mut BitWriter<W>) -> Result<(), std::io::Error> {
+        w.write_bit(*self)
+    }
+}
+
+impl<T, const N: usize> BitDeserialize for [T; N]
+where
+    T: BitDeserialize,
+{
+    fn bit_deserialize<R: Read>(r: &mut BitReader<R>) -> Result<Self, std::io::Error> {
+        let mut result = Vec::new();
+
+        for _ in 0..N {
+            result.push(T::bit_deserialize(r)?);
+        }
+
+        // See the `Deserialize` implementation.
+        //
+        if let Ok(result) = result.try_into() {
+            Ok(result)
+        } else {
+            unreachable!()
+        }
+    }
+}
+
+impl<T, const N: usize> BitSerialize for [T; N]
+where
+    T: BitSerialize,
+{
+    fn bit_serialize<W: Write>(&self, w: &mut BitWriter<W>) -> Result<(), std::io::Error> {
+        for instance in self {
+            instance.bit_serialize(w)?;
+        }
+        Ok(())
+    }
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// FUNCTIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+/// Reads a value stored in the given number of bits; used by the derived implementations.
+///
+pub fn read_field<T: FromBits, R: Read>(
+    r: &mut BitReader<R>,
+    width: u32,
+    field: &'static str,
+) -> Result<T, std::io::Error> {
+    let bits = r.read_bits(width)?;
+
+    T::from_bits(bits, width).ok_or_else(|| Error::InvalidBitfieldValue { field, bits }.into())
+}
+
+/// Writes a value in the given number of bits; used by the derived implementations.
+///
+pub fn write_field<T: ToBits, W: Write>(
+    value: &T,
+    w: &mut BitWriter<W>,
+    width: u32,
+    field: &'static str,
+) -> Result<(), std::io::Error> {
+    match value.to_bits(width) {
+        Some(bits) if width == MAX_BITS || bits >> width == 0 => w.write_bits(bits, width),
+        _ => Err(Error::BitfieldOverflow { field, width }.into()),
+    }
+}
+
+/// Deserializes a bit-level value from a byte-oriented reader; the bits remaining in the last byte
+/// are discarded.
+///
+pub fn deserialize_bits<T: BitDeserialize, R: Read>(
+    r: R,
+    bit_order: BitOrder,
+) -> Result<T, std::io::Error> {
+    T::bit_deserialize(&mut BitReader::new(r, bit_order))
+}
+
+/// Serializes a bit-level value to a byte-oriented writer; the last byte is padded with zeros.
+///
+pub fn serialize_bits<T: BitSerialize, W: Write>(
+    value: &T,
+    w: W,
+    bit_order: BitOrder,
+) -> Result<(), std::io::Error> {
+    let mut bit_writer = BitWriter::new(w, bit_order);
+    value.bit_serialize(&mut bit_writer)?;
+    bit_writer.finish()?;
+    Ok(())
+}
@@ -0,0 +1,76 @@
+//! Bool layouts.
+//!
+//! The trait implementation stores bools as a `u8` (0 or 1); the other layouts are available as
+//! functions, which are what the `bool_layout` field attribute is based on.
+//!
+//! On deserialization, values other than the false and true ones are considered true, unless strict
+//! mode is enabled (via the `strict_bool` field attribute, or via `Options`), in which case they
+//! fail with `Error::InvalidBool`.
+
+use std::io::{Read, Write};
+
+use crate::options::current_options;
+use crate::{Deserialize, Error, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoolLayout {
+    /// 0 or 1.
+    U8,
+    /// 0 or 1, stored as `u16` (e.g. 16-bit C `BOOL`).
+    U16,
+    /// 0 or 1, stored as `u32` (e.g. Win32 `BOOL`).
+    U32,
+    /// 0 or 0xFF.
+    U8Ff,
+}
+
+impl BoolLayout {
+    /// Serialized size, in bytes.
+    ///
+    pub const fn size(&self) -> usize {
+        match self {
+            BoolLayout::U8 | BoolLayout::U8Ff => 1,
+            BoolLayout::U16 => 2,
+            BoolLayout::U32 => 4,
+        }
+    }
+
+    fn true_value(&self) -> u32 {
+        match self {
+            BoolLayout::U8 | BoolLayout::U16 | BoolLayout::U32 => 1,
+            BoolLayout::U8Ff => 0xFF,
+        }
+    }
+}
+
+pub fn deserialize<R: Read>(
+    r: R,
+    layout: BoolLayout,
+    strict: bool,
+) -> Result<bool, std::io::Error> {
+    let value = match layout {
+        BoolLayout::U8 | BoolLayout::U8Ff => u8::deserialize(r)? as u32,
+        BoolLayout::U16 => u16::deserialize(r)? as u32,
+        BoolLayout::U32 => u32::deserialize(r)?,
+    };
+
+    let strict = strict || current_options().strict_bool();
+
+    if value == 0 {
+        Ok(false)
+    } else if value == layout.true_value() || !strict {
+        Ok(true)
+    } else {
+        Err(Error::InvalidBool { value }.into())
+    }
+}
+
+pub fn serialize<W: Write>(value: &bool, w: W, layout: BoolLayout) -> Result<(), std::io::Error> {
+    let value = if *value { layout.true_value() } else { 0 };
+
+    match layout {
+        BoolLayout::U8 | BoolLayout::U8Ff => (value as u8).serialize(w),
+        BoolLayout::U16 => (value as u16).serialize(w),
+        BoolLayout::U32 => value.serialize(w),
+    }
+}
@@ -0,0 +1,406 @@
+//! De/serialization of collections.
+//!
+//! The trait implementations store the number of items as a `u32` prefix, followed by the items;
+//! maps store each entry as key followed by value. The prefix type can be changed via the
+//! `len_prefix` field attribute, or the prefix can be omitted, with the number of items specified
+//! by an expression (e.g. a previous field), via the `count` field attribute.
+//!
+//! `HashMap`/`HashSet` are serialized in key order, so that the output is reproducible.
+//!
+//! `String`s are stored as their UTF-8 bytes, with the length in bytes; invalid UTF-8 is handled
+//! according to the string policy of the current options.
+//!
+//! The length and the memory of the items are checked against the current limits (see the `limits`
+//! module) before deserializing the items.
+//!
+//! Since collections can be the indirection of recursive types, the items are de/serialized via a
+//! type-erased reader/writer, so that the nested calls don't instantiate the trait methods with
+//! ever-growing `&mut &mut ...` types.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::convert::TryFrom;
+use std::hash::{BuildHasher, Hash};
+use std::io::{Read, Write};
+
+use crate::options::{current_options, StringPolicy};
+use crate::{limits, Deserialize, Error, Serialize};
+
+// Upper bound to the capacity allocated before deserializing the items, so that a corrupt length
+// doesn't cause large allocations upfront.
+//
+const MAX_PREALLOCATED_ITEMS: usize = 1024;
+
+// ////////////////////////////////////////////////////////////////////////////////
+// TRAITS
+// ////////////////////////////////////////////////////////////////////////////////
+
+/// Types that can be used as length prefix.
+///
+pub trait LengthPrefix: Deserialize + Serialize {
+    fn from_len(len: usize) -> Result<Self, std::io::Error>;
+
+    fn to_len(&self) -> Result<usize, std::io::Error>;
+}
+
+macro_rules! impl_length_prefix {
+    ( $( $type:ty ),+ ) => {
+        $(
+            impl LengthPrefix for $type {
+                fn from_len(len: usize) -> Result<Self, std::io::Error> {
+                    <$type>::try_from(len).map_err(|_| {
+                        Error::IntegerOverflow {
+                            value: len as i128,
+                            width: std::mem::size_of::<$type>(),
+                        }
+                        .into()
+                    })
+                }
+
+                fn to_len(&self) -> Result<usize, std::io::Error> {
+                    usize::try_from(*self).map_err(|_| {
+                        Error::IntegerOverflow {
+                            value: *self as i128,
+                            width: std::mem::size_of::<$type>(),
+                        }
+                        .into()
+                    })
+                }
+            }
+        )+
+    };
+}
+
+impl_length_prefix!(u8, u16, u32, u64);
+
+pub trait DeserializeCollection: Sized {
+    fn deserialize_items<R: Read>(r: R, len: usize) -> Result<Self, std::io::Error>;
+}
+
+pub trait SerializeCollection {
+    fn items_count(&self) -> usize;
+
+    fn serialize_items<W: Write>(&self, w: W) -> Result<(), std::io::Error>;
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// FUNCTIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+pub fn deserialize_with_prefix<P, C, R>(mut r: R) -> Result<C, std::io::Error>
+where
+    P: LengthPrefix,
+    C: DeserializeCollection,
+    R: Read,
+{
+    let len = P::deserialize(&mut r)?.to_len()?;
+
+    let _nesting_guard = limits::enter_nesting()?;
+
+    C::deserialize_items(&mut r as &mut dyn Read, len)
+}
+
+pub fn serialize_with_prefix<P, C, W>(collection: &C, mut w: W) -> Result<(), std::io::Error>
+where
+    P: LengthPrefix,
+    C: SerializeCollection,
+    W: Write,
+{
+    P::from_len(collection.items_count())?.serialize(&mut w)?;
+
+    collection.serialize_items(&mut w as &mut dyn Write)
+}
+
+pub fn deserialize_with_count<C, R>(mut r: R, count: usize) -> Result<C, std::io::Error>
+where
+    C: DeserializeCollection,
+    R: Read,
+{
+    let _nesting_guard = limits::enter_nesting()?;
+
+    C::deserialize_items(&mut r as &mut dyn Read, count)
+}
+
+/// Fails if the number of items doesn't match `count`, since the collection wouldn't be read back
+/// correctly.
+///
+pub fn serialize_with_count<C, W>(
+    collection: &C,
+    mut w: W,
+    count: usize,
+) -> Result<(), std::io::Error>
+where
+    C: SerializeCollection,
+    W: Write,
+{
+    let actual = collection.items_count();
+
+    if actual != count {
+        return Err(Error::CountMismatch {
+            expected: count,
+            actual,
+        }
+        .into());
+    }
+
+    collection.serialize_items(&mut w as &mut dyn Write)
+}
+
+/// Converts the result of a `count` expression; used by the derived implementations.
+///
+pub fn count_to_len<T: TryInto<usize>>(count: T) -> Result<usize, std::io::Error> {
+    count.try_into().map_err(|_| Error::InvalidCount.into())
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// IMPLEMENTATIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+macro_rules! impl_traits_with_prefix {
+    ( $( [ $( $generics:tt )* ] $type:ty ),+ $(,)? ) => {
+        $(
+            impl<$( $generics )*> Deserialize for $type
+            where
+                $type: DeserializeCollection,
+            {
+                fn deserialize<R: Read>(r: R) -> Result<Self, std::io::Error> {
+                    deserialize_with_prefix::<u32, Self, R>(r)
+                }
+            }
+
+            impl<$( $generics )*> Serialize for $type
+            where
+                $type: SerializeCollection,
+            {
+                fn serialize<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+                    serialize_with_prefix::<u32, Self, W>(self, w)
+                }
+            }
+        )+
+    };
+}
+
+impl_traits_with_prefix!(
+    [T] Vec<T>,
+    [T] VecDeque<T>,
+    [T] BTreeSet<T>,
+    [K, V] BTreeMap<K, V>,
+    [T, S] HashSet<T, S>,
+    [K, V, S] HashMap<K, V, S>,
+    [] String,
+);
+
+// Sequences
+
+macro_rules! impl_collection_for_sequence {
+    ( $( $type:ident => $insert_fn:ident ),+ ) => {
+        $(
+            impl<T: Deserialize> DeserializeCollection for $type<T> {
+                fn deserialize_items<R: Read>(mut r: R, len: usize) -> Result<Self, std::io::Error> {
+                    limits::charge_collection::<T>(len)?;
+
+                    let mut result = $type::with_capacity(len.min(MAX_PREALLOCATED_ITEMS));
+
+                    for _ in 0..len {
+                        result.$insert_fn(T::deserialize(&mut r)?);
+                    }
+
+                    Ok(result)
+                }
+            }
+
+            impl<T: Serialize> SerializeCollection for $type<T> {
+                fn items_count(&self) -> usize {
+                    self.len()
+                }
+
+                fn serialize_items<W: Write>(&self, mut w: W) -> Result<(), std::io::Error> {
+                    for item in self {
+                        item.serialize(&mut w)?;
+                    }
+                    Ok(())
+                }
+            }
+        )+
+    };
+}
+
+impl_collection_for_sequence!(Vec => push, VecDeque => push_back);
+
+// Strings; the items are the UTF-8 bytes, decoded according to the current string policy.
+
+impl DeserializeCollection for String {
+    fn deserialize_items<R: Read>(mut r: R, len: usize) -> Result<Self, std::io::Error> {
+        limits::charge_collection::<u8>(len)?;
+
+        let mut bytes = Vec::with_capacity(len.min(MAX_PREALLOCATED_ITEMS));
+        r.by_ref().take(len as u64).read_to_end(&mut bytes)?;
+
+        if bytes.len() < len {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+
+        match current_options().string_policy() {
+            StringPolicy::Strict => String::from_utf8(bytes).map_err(|_| Error::InvalidUtf8.into()),
+            StringPolicy::Lossy => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+        }
+    }
+}
+
+impl SerializeCollection for String {
+    fn items_count(&self) -> usize {
+        self.len()
+    }
+
+    fn serialize_items<W: Write>(&self, mut w: W) -> Result<(), std::io::Error> {
+        w.write_all(self.as_bytes())
+    }
+}
+
+// Sets and maps; duplicate keys are not rejected, and the last one is retained.
+
+impl<T: Deserialize + Ord> DeserializeCollection for BTreeSet<T> {
+    fn deserialize_items<R: Read>(mut r: R, len: usize) -> Result<Self, std::io::Error> {
+        limits::charge_collection::<T>(len)?;
+
+        let mut result = BTreeSet::new();
+
+        for _ in 0..len {
+            result.insert(T::deserialize(&mut r)?);
+        }
+
+        Ok(result)
+    }
+}
+
+impl<T: Serialize> SerializeCollection for BTreeSet<T> {
+    fn items_count(&self) -> usize {
+        self.len()
+    }
+
+    fn serialize_items<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+        serialize_keys(self.iter(), w)
+    }
+}
+
+impl<K: Deserialize + Ord, V: Deserialize> DeserializeCollection for BTreeMap<K, V> {
+    fn deserialize_items<R: Read>(mut r: R, len: usize) -> Result<Self, std::io::Error> {
+        limits::charge_collection::<(K, V)>(len)?;
+
+        let mut result = BTreeMap::new();
+
+        for _ in 0..len {
+            let key = K::deserialize(&mut r)?;
+            let value = V::deserialize(&mut r)?;
+            result.insert(key, value);
+        }
+
+        Ok(result)
+    }
+}
+
+impl<K: Serialize, V: Serialize> SerializeCollection for BTreeMap<K, V> {
+    fn items_count(&self) -> usize {
+        self.len()
+    }
+
+    fn serialize_items<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+        serialize_entries(self.iter(), w)
+    }
+}
+
+impl<T, S> DeserializeCollection for HashSet<T, S>
+where
+    T: Deserialize + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn deserialize_items<R: Read>(mut r: R, len: usize) -> Result<Self, std::io::Error> {
+        limits::charge_collection::<T>(len)?;
+
+        let capacity = len.min(MAX_PREALLOCATED_ITEMS);
+        let mut result = HashSet::with_capacity_and_hasher(capacity, S::default());
+
+        for _ in 0..len {
+            result.insert(T::deserialize(&mut r)?);
+        }
+
+        Ok(result)
+    }
+}
+
+impl<T: Serialize + Ord, S> SerializeCollection for HashSet<T, S> {
+    fn items_count(&self) -> usize {
+        self.len()
+    }
+
+    fn serialize_items<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+        let mut keys = self.iter().collect::<Vec<_>>();
+        keys.sort();
+
+        serialize_keys(keys.into_iter(), w)
+    }
+}
+
+impl<K, V, S> DeserializeCollection for HashMap<K, V, S>
+where
+    K: Deserialize + Eq + Hash,
+    V: Deserialize,
+    S: BuildHasher + Default,
+{
+    fn deserialize_items<R: Read>(mut r: R, len: usize) -> Result<Self, std::io::Error> {
+        limits::charge_collection::<(K, V)>(len)?;
+
+        let capacity = len.min(MAX_PREALLOCATED_ITEMS);
+        let mut result = HashMap::with_capacity_and_hasher(capacity, S::default());
+
+        for _ in 0..len {
+            let key = K::deserialize(&mut r)?;
+            let value = V::deserialize(&mut r)?;
+            result.insert(key, value);
+        }
+
+        Ok(result)
+    }
+}
+
+impl<K: Serialize + Ord, V: Serialize, S> SerializeCollection for HashMap<K, V, S> {
+    fn items_count(&self) -> usize {
+        self.len()
+    }
+
+    fn serialize_items<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+        let mut entries = self.iter().collect::<Vec<_>>();
+        entries.sort_by_key(|(key, _)| *key);
+
+        serialize_entries(entries.into_iter(), w)
+    }
+}
+
+fn serialize_keys<'a, T, W>(
+    keys: impl Iterator<Item = &'a T>,
+    mut w: W,
+) -> Result<(), std::io::Error>
+where
+    T: Serialize + 'a,
+    W: Write,
+{
+    for key in keys {
+        key.serialize(&mut w)?;
+    }
+    Ok(())
+}
+
+fn serialize_entries<'a, K, V, W>(
+    entries: impl Iterator<Item = (&'a K, &'a V)>,
+    mut w: W,
+) -> Result<(), std::io::Error>
+where
+    K: Serialize + 'a,
+    V: Serialize + 'a,
+    W: Write,
+{
+    for (key, value) in entries {
+        key.serialize(&mut w)?;
+        value.serialize(&mut w)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use std::io::{Read, Write};
+
+/// Deserialization that requires information from outside the value (e.g. a version number from a
+/// file header).
+///
+/// Derived for structs with the `ctx` container attribute; the context is available to the
+/// field attribute expressions (as `ctx`), and is passed to the custom deserialization functions,
+/// as additional argument. The `ctx` field attribute passes a context to the field.
+///
+pub trait DeserializeWithCtx<C>: Sized {
+    fn deserialize_with_ctx<R: Read>(r: R, ctx: &C) -> Result<Self, std::io::Error>;
+}
+
+/// Counterpart of `DeserializeWithCtx`.
+///
+pub trait SerializeWithCtx<C> {
+    fn serialize_with_ctx<W: Write>(&self, w: W, ctx: &C) -> Result<(), std::io::Error>;
+}
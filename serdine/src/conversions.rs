@@ -0,0 +1,52 @@
+//! De/serialization via an intermediate (stored) type.
+//!
+//! Values are converted via `TryFrom`, so that narrowing conversions (e.g. `usize` stored as `u16`)
+//! are checked; conversion errors are reported as `Error::ConversionFailed`, with the original
+//! error as source. Infallible conversions (`From`) are supported as well, since they imply
+//! `TryFrom`.
+//!
+//! These functions are what the `as` field attribute, and the `from`/`try_from`/`into` container
+//! attributes are based on.
+
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+
+use crate::{Deserialize, Error, Serialize};
+
+/// Deserializes a value of the stored type `S`, and converts it to `T`.
+///
+pub fn deserialize_as<S, T, R>(r: R) -> Result<T, std::io::Error>
+where
+    S: Deserialize,
+    T: TryFrom<S>,
+    T::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    R: Read,
+{
+    let stored = S::deserialize(r)?;
+
+    T::try_from(stored).map_err(conversion_error)
+}
+
+/// Converts a value to the stored type `S`, and serializes it.
+///
+pub fn serialize_as<S, T, W>(value: &T, w: W) -> Result<(), std::io::Error>
+where
+    S: Serialize + TryFrom<T>,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    T: Clone,
+    W: Write,
+{
+    let stored = S::try_from(value.clone()).map_err(conversion_error)?;
+
+    stored.serialize(w)
+}
+
+pub fn conversion_error<E>(error: E) -> std::io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    Error::ConversionFailed {
+        source: error.into(),
+    }
+    .into()
+}
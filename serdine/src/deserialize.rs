@@ -1,5 +1,20 @@
 use std::io::Read;
 
+use crate::options::{self, Options};
+
 pub trait Deserialize: Sized {
     fn deserialize<R: Read>(r: R) -> Result<Self, std::io::Error>;
+
+    /// Deserializes with the given options, instead of the default ones.
+    ///
+    fn deserialize_with_options<R: Read>(
+        mut r: R,
+        options: &Options,
+    ) -> Result<Self, std::io::Error> {
+        options::with_options(options, || {
+            let result = Self::deserialize(&mut r)?;
+            options::check_trailing_bytes(r)?;
+            Ok(result)
+        })
+    }
 }
@@ -1,6 +1,6 @@
 use crate as serdine;
 use crate::Deserialize as DeserializeDisambiguate;
-use serdine_derive::Deserialize;
+use serdine_derive::{BitDeserialize, Deserialize};
 
 // ////////////////////////////////////////////////////////////////////////////////
 // STRUCT WITH NAMED FIELDS
@@ -54,7 +54,7 @@ fn test_deserialize_named_fields_struct() {
 
 #[derive(Debug, Deserialize, PartialEq)]
 #[repr(u16)]
-enum MyEnum {
+pub enum MyEnum {
     VarA = 0,
     VarB = 1,
     VarC = 65534,
@@ -84,3 +84,1706 @@ fn test_deserialize_enum() {
         serdine::Deserialize::deserialize(&mut reader).unwrap()
     );
 }
+
+#[test]
+fn test_deserialize_enum_unknown_variant() {
+    let serialized_bytes: &[u8] = &[0x02, 0x00];
+
+    let error = MyEnum::deserialize(serialized_bytes).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::UnknownVariant {
+            type_name: "MyEnum",
+            value: 2
+        })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// ODD-WIDTH INTEGERS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Deserialize)]
+pub struct MyOddWidthStruct {
+    pub my_u24: serdine::U24,
+    pub my_i24: serdine::I24,
+    #[serdine(endian = "big")]
+    pub my_u48_be: serdine::U48,
+    #[serdine(width = 3)]
+    pub my_u32: u32,
+    #[serdine(width = 3, endian = "big")]
+    pub my_i32_be: i32,
+}
+
+#[test]
+fn test_deserialize_odd_width_integers() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x03, 0x02, 0x01,
+        0xFE, 0xFF, 0xFF,
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+        0xBE, 0xBA, 0xFE,
+        0xFF, 0xFF, 0xFD,
+    ];
+
+    let instance = MyOddWidthStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(0x010203, instance.my_u24.get());
+    assert_eq!(-2, instance.my_i24.get());
+    assert_eq!(0x010203040506, instance.my_u48_be.get());
+    assert_eq!(0xFEBABE, instance.my_u32);
+    assert_eq!(-3, instance.my_i32_be);
+}
+
+#[derive(Deserialize)]
+pub struct MyNarrowStruct {
+    #[serdine(width = 3)]
+    pub my_u16: u16,
+}
+
+#[test]
+fn test_deserialize_odd_width_integer_overflow() {
+    let serialized_bytes: &[u8] = &[0xFF, 0xFF, 0x00];
+
+    let instance = MyNarrowStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(0xFFFF, instance.my_u16);
+
+    let serialized_bytes: &[u8] = &[0xFF, 0xFF, 0x01];
+
+    let error = MyNarrowStruct::deserialize(serialized_bytes).err().unwrap();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::IntegerOverflow {
+            value: 0x01FFFF,
+            width: 3
+        })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// VARIABLE-LENGTH INTEGERS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Deserialize)]
+pub struct MyVarintStruct {
+    #[serdine(encoding = "uleb128")]
+    pub my_uleb128: u32,
+    #[serdine(encoding = "sleb128")]
+    pub my_sleb128: i32,
+    #[serdine(encoding = "zigzag")]
+    pub my_zigzag: i64,
+    #[serdine(encoding = "vlq")]
+    pub my_vlq: u32,
+    pub my_wrapped_uleb128: serdine::varint::Uleb128<u8>,
+}
+
+#[test]
+fn test_deserialize_varints() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0xE5, 0x8E, 0x26,
+        0xC0, 0xBB, 0x78,
+        0x03,
+        0x81, 0x80, 0x00,
+        0x7F,
+    ];
+
+    let instance = MyVarintStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(624485, instance.my_uleb128);
+    assert_eq!(-123456, instance.my_sleb128);
+    assert_eq!(-2, instance.my_zigzag);
+    assert_eq!(0x4000, instance.my_vlq);
+    assert_eq!(0x7F, instance.my_wrapped_uleb128.0);
+}
+
+#[test]
+fn test_deserialize_varint_errors() {
+    fn deserialize_error<T: serdine::Deserialize>(mut serialized_bytes: &[u8]) -> serdine::Error {
+        let error = T::deserialize(&mut serialized_bytes).err().unwrap();
+        *error.into_inner().unwrap().downcast().unwrap()
+    }
+
+    use serdine::varint::{Sleb128, Uleb128, Vlq};
+
+    assert!(matches!(
+        deserialize_error::<Uleb128<u32>>(&[0x80, 0x00]),
+        serdine::Error::OverlongEncoding
+    ));
+    assert!(matches!(
+        deserialize_error::<Sleb128<i32>>(&[0xFF, 0x7F]),
+        serdine::Error::OverlongEncoding
+    ));
+    assert!(matches!(
+        deserialize_error::<Vlq<u32>>(&[0x80, 0x01]),
+        serdine::Error::OverlongEncoding
+    ));
+    assert!(matches!(
+        deserialize_error::<Uleb128<u8>>(&[0x80, 0x02]),
+        serdine::Error::VarintOutOfRange
+    ));
+    assert!(matches!(
+        deserialize_error::<Uleb128<u8>>(&[0x80, 0x81, 0x01]),
+        serdine::Error::VarintOutOfRange
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// BITFIELDS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[repr(u8)]
+enum MyBitsEnum {
+    VarA = 0,
+    VarB = 1,
+    // Suffixed discriminants are supported as well.
+    VarC = 2u8,
+}
+
+#[derive(Deserialize)]
+#[serdine(bitfield = "u16")]
+pub struct MyBitfield {
+    #[serdine(bits = 3)]
+    pub my_u8: u8,
+    #[serdine(bits = 1)]
+    pub my_bool: bool,
+    #[serdine(bits = 2)]
+    my_enum: MyBitsEnum,
+    #[serdine(bits = 4)]
+    pub my_i8: i8,
+}
+
+#[derive(Deserialize)]
+#[serdine(bitfield = "u8", bit_order = "msb")]
+pub struct MyMsbFirstBitfield {
+    #[serdine(bits = 1)]
+    pub my_bool: bool,
+    #[serdine(bits = 3)]
+    pub my_u8: u8,
+}
+
+#[test]
+fn test_deserialize_bitfields() {
+    let serialized_bytes: &[u8] = &[0x6D, 0x03];
+
+    let instance = MyBitfield::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(5, instance.my_u8);
+    assert!(instance.my_bool);
+    assert_eq!(MyBitsEnum::VarC, instance.my_enum);
+    assert_eq!(-3, instance.my_i8);
+
+    let serialized_bytes: &[u8] = &[0xD0];
+
+    let instance = MyMsbFirstBitfield::deserialize(serialized_bytes).unwrap();
+
+    assert!(instance.my_bool);
+    assert_eq!(5, instance.my_u8);
+}
+
+#[test]
+fn test_deserialize_bitfield_invalid_value() {
+    let serialized_bytes: &[u8] = &[0x30, 0x00];
+
+    let error = MyBitfield::deserialize(serialized_bytes).err().unwrap();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::InvalidBitfieldValue {
+            field: "my_enum",
+            bits: 0b11
+        })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// BIT-LEVEL STRUCTS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(BitDeserialize)]
+pub struct MyBitLevelStruct {
+    #[serdine(bits = 3)]
+    pub my_u8: u8,
+    pub my_bool: bool,
+    #[serdine(bits = 11)]
+    pub my_u16: u16,
+    #[serdine(align)]
+    pub my_aligned_u8: u8,
+}
+
+#[derive(Deserialize)]
+pub struct MyMixedLevelStruct {
+    pub my_u8: u8,
+    #[serdine(bit_order = "msb")]
+    pub my_bits: MyBitLevelStruct,
+    pub my_u16: u16,
+}
+
+#[test]
+fn test_deserialize_bit_level_struct() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x11,
+        0xBA, 0xBD, 0x7F,
+        0x34, 0x12,
+    ];
+
+    let instance = MyMixedLevelStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(0x11, instance.my_u8);
+    assert_eq!(5, instance.my_bits.my_u8);
+    assert!(instance.my_bits.my_bool);
+    assert_eq!(0x55E, instance.my_bits.my_u16);
+    assert_eq!(0x7F, instance.my_bits.my_aligned_u8);
+    assert_eq!(0x1234, instance.my_u16);
+}
+
+#[test]
+fn test_bit_reader_lsb_first() {
+    let serialized_bytes: &[u8] = &[0b1010_1100, 0b0000_0001];
+
+    let mut reader = serdine::bits::BitReader::new(serialized_bytes, serdine::BitOrder::LsbFirst);
+
+    assert_eq!(0b100, reader.read_bits(3).unwrap());
+    assert_eq!(0b1_0101, reader.read_bits(5).unwrap());
+    assert!(reader.is_aligned());
+    assert!(reader.read_bit().unwrap());
+
+    let error = reader.read_bits(65).err().unwrap();
+
+    assert_eq!(std::io::ErrorKind::InvalidInput, error.kind());
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// BITFLAGS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "bitflags")]
+bitflags::bitflags! {
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serdine(bitflags = "reject")]
+    pub struct MyRejectingFlags: u8 {
+        const FLAG_A = 0b01;
+        const FLAG_B = 0b10;
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serdine(bitflags = "truncate")]
+    pub struct MyTruncatingFlags: u8 {
+        const FLAG_A = 0b01;
+        const FLAG_B = 0b10;
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serdine(bitflags = "retain")]
+    pub struct MyRetainingFlags: u16 {
+        const FLAG_A = 0b01;
+        const FLAG_B = 0b10;
+    }
+}
+
+#[cfg(feature = "bitflags")]
+#[test]
+fn test_deserialize_bitflags() {
+    let serialized_bytes: &[u8] = &[0x03];
+
+    assert_eq!(
+        MyRejectingFlags::FLAG_A | MyRejectingFlags::FLAG_B,
+        MyRejectingFlags::deserialize(serialized_bytes).unwrap()
+    );
+
+    let serialized_bytes: &[u8] = &[0x81];
+
+    let error = MyRejectingFlags::deserialize(serialized_bytes)
+        .err()
+        .unwrap();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::UnknownFlagBits { .. })
+    ));
+
+    assert_eq!(
+        MyTruncatingFlags::FLAG_A,
+        MyTruncatingFlags::deserialize(serialized_bytes).unwrap()
+    );
+
+    let serialized_bytes: &[u8] = &[0x01, 0x80];
+
+    assert_eq!(
+        0x8001,
+        MyRetainingFlags::deserialize(serialized_bytes)
+            .unwrap()
+            .bits()
+    );
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// STANDARD LIBRARY TYPES
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Deserialize)]
+pub struct MyStdTypesStruct {
+    pub my_tuple: (u8, i16),
+    pub my_char: char,
+    pub my_non_zero: std::num::NonZeroU16,
+    pub my_wrapping: std::num::Wrapping<u8>,
+    pub my_usize: usize,
+    pub my_box: Box<u16>,
+    pub my_cell: std::cell::Cell<u8>,
+}
+
+#[test]
+fn test_deserialize_std_types() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x01, 0xFE, 0xFF,
+        0x41, 0x00, 0x00, 0x00,
+        0x02, 0x00,
+        0x03,
+        0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x05, 0x00,
+        0x06,
+    ];
+
+    let instance = MyStdTypesStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!((1, -2), instance.my_tuple);
+    assert_eq!('A', instance.my_char);
+    assert_eq!(2, instance.my_non_zero.get());
+    assert_eq!(3, instance.my_wrapping.0);
+    assert_eq!(4, instance.my_usize);
+    assert_eq!(5, *instance.my_box);
+    assert_eq!(6, instance.my_cell.get());
+}
+
+#[test]
+fn test_deserialize_std_types_invalid_values() {
+    let serialized_bytes: &[u8] = &[0x00, 0xD8, 0x00, 0x00];
+
+    let error = char::deserialize(serialized_bytes).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::InvalidChar { value: 0xD800 })
+    ));
+
+    let serialized_bytes: &[u8] = &[0x00, 0x00];
+
+    let error = std::num::NonZeroU16::deserialize(serialized_bytes).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::UnexpectedZero { .. })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// OPTIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Deserialize)]
+pub struct MyOptionsStruct {
+    pub my_default: Option<u8>,
+    #[serdine(presence = "u16")]
+    pub my_presence: Option<u16>,
+    #[serdine(none_value = 0xFFFF)]
+    pub my_sentinel: Option<u16>,
+    #[serdine(none_value = "-1")]
+    pub my_negative_sentinel: Option<i32>,
+    #[serdine(none_if_zeroed)]
+    pub my_zeroed: Option<[u8; 3]>,
+}
+
+#[test]
+fn test_deserialize_options() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x01, 0x05,
+        0x01, 0x00, 0x06, 0x00,
+        0x07, 0x00,
+        0x08, 0x00, 0x00, 0x00,
+        0x00, 0x09, 0x00,
+    ];
+
+    let instance = MyOptionsStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(Some(5), instance.my_default);
+    assert_eq!(Some(6), instance.my_presence);
+    assert_eq!(Some(7), instance.my_sentinel);
+    assert_eq!(Some(8), instance.my_negative_sentinel);
+    assert_eq!(Some([0x00, 0x09, 0x00]), instance.my_zeroed);
+
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x00,
+        0x00, 0x00,
+        0xFF, 0xFF,
+        0xFF, 0xFF, 0xFF, 0xFF,
+        0x00, 0x00, 0x00,
+    ];
+
+    let instance = MyOptionsStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(None, instance.my_default);
+    assert_eq!(None, instance.my_presence);
+    assert_eq!(None, instance.my_sentinel);
+    assert_eq!(None, instance.my_negative_sentinel);
+    assert_eq!(None, instance.my_zeroed);
+}
+
+#[test]
+fn test_deserialize_options_invalid_presence_tag() {
+    let serialized_bytes: &[u8] = &[0x02, 0x05];
+
+    let error = Option::<u8>::deserialize(serialized_bytes).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::InvalidPresenceTag { value: 2 })
+    ));
+}
+
+#[derive(Deserialize)]
+pub struct MyNonZeroOptionStruct {
+    #[serdine(none_if_zeroed)]
+    pub my_non_zero: Option<std::num::NonZeroU16>,
+}
+
+// The zeroed `None` is read back also for types that reject zero values.
+//
+#[test]
+fn test_deserialize_options_zeroed_non_zero_round_trip() {
+    for value in [None, std::num::NonZeroU16::new(0x0102)] {
+        let mut serialized_bytes = Vec::new();
+
+        serdine::option::serialize_if_zeroed(&value, &mut serialized_bytes).unwrap();
+
+        let instance = MyNonZeroOptionStruct::deserialize(serialized_bytes.as_slice()).unwrap();
+
+        assert_eq!(value, instance.my_non_zero);
+    }
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// COLLECTIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Deserialize)]
+pub struct MyCollectionsStruct {
+    pub my_vec: Vec<u16>,
+    #[serdine(len_prefix = "u8")]
+    pub my_deque: std::collections::VecDeque<u8>,
+    pub my_len: u16,
+    #[serdine(count = "my_len")]
+    pub my_set: std::collections::BTreeSet<u8>,
+    #[serdine(len_prefix = "u16")]
+    pub my_map: std::collections::HashMap<u8, u16>,
+    pub my_hash_set: std::collections::HashSet<u8>,
+}
+
+#[test]
+fn test_deserialize_collections() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00,
+        0x01, 0x03,
+        0x02, 0x00,
+        0x05, 0x04,
+        0x02, 0x00, 0x06, 0x07, 0x00, 0x08, 0x09, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x0A,
+    ];
+
+    let instance = MyCollectionsStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(vec![1, 2], instance.my_vec);
+    assert_eq!(vec![3], Vec::from(instance.my_deque));
+    assert_eq!(2, instance.my_len);
+    assert_eq!(vec![4, 5], instance.my_set.into_iter().collect::<Vec<_>>());
+    assert_eq!(
+        std::collections::HashMap::from([(6, 7), (8, 9)]),
+        instance.my_map
+    );
+    assert_eq!(std::collections::HashSet::from([10]), instance.my_hash_set);
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// SHARED REFERENCES
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Deserialize)]
+pub struct MyLevel {
+    pub my_objects: Vec<std::rc::Rc<u16>>,
+    pub my_selection: Vec<std::rc::Rc<u16>>,
+}
+
+#[derive(Deserialize)]
+pub struct MyWeakSelection {
+    pub my_objects: Vec<std::rc::Rc<u16>>,
+    pub my_selection: std::rc::Weak<u16>,
+}
+
+#[derive(Deserialize)]
+pub struct MyParentNode {
+    pub my_value: u8,
+    pub my_children: Vec<std::rc::Rc<MyChildNode>>,
+}
+
+#[derive(Deserialize)]
+pub struct MyChildNode {
+    pub my_value: u8,
+    pub my_parent: std::rc::Weak<MyParentNode>,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+pub struct MyStrongParentNode {
+    pub my_child: Box<MyStrongChildNode>,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+pub struct MyStrongChildNode {
+    pub my_parent: std::rc::Rc<MyStrongParentNode>,
+}
+
+#[test]
+fn test_deserialize_shared_references() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x0A, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x0B, 0x00,
+        0x01, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00,
+    ];
+
+    let instance: MyLevel =
+        serdine::shared::deserialize_with_object_table(serialized_bytes).unwrap();
+
+    assert_eq!(10, *instance.my_objects[0]);
+    assert_eq!(11, *instance.my_objects[1]);
+    assert!(std::rc::Rc::ptr_eq(
+        &instance.my_objects[1],
+        &instance.my_selection[0]
+    ));
+}
+
+#[test]
+fn test_deserialize_shared_references_weak() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x0A, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+    ];
+
+    let instance: MyWeakSelection =
+        serdine::shared::deserialize_with_object_table(serialized_bytes).unwrap();
+
+    let selection = instance.my_selection.upgrade().unwrap();
+
+    assert!(std::rc::Rc::ptr_eq(&instance.my_objects[0], &selection));
+}
+
+#[test]
+fn test_deserialize_shared_references_weak_to_value_in_construction() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, 0x01,
+        0x01, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x02,
+        0x00, 0x00, 0x00, 0x00,
+    ];
+
+    let instance: std::rc::Rc<MyParentNode> =
+        serdine::shared::deserialize_with_object_table(serialized_bytes).unwrap();
+
+    let child = &instance.my_children[0];
+    let parent = child.my_parent.upgrade().unwrap();
+
+    assert_eq!(1, instance.my_value);
+    assert_eq!(2, child.my_value);
+    assert!(std::rc::Rc::ptr_eq(&instance, &parent));
+}
+
+// The value in construction is discarded, while the back-references to it are still alive.
+//
+#[test]
+fn test_deserialize_shared_references_weak_to_value_in_construction_truncated() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, 0x01,
+        0x02, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x02,
+        0x00, 0x00, 0x00, 0x00,
+    ];
+
+    let error = serdine::shared::deserialize_with_object_table::<std::rc::Rc<MyParentNode>, _>(
+        serialized_bytes,
+    )
+    .err()
+    .unwrap();
+
+    assert_eq!(std::io::ErrorKind::UnexpectedEof, error.kind());
+}
+
+// Strong references to values in construction would be reference cycles.
+//
+#[test]
+fn test_deserialize_shared_references_strong_to_value_in_construction() {
+    let serialized_bytes: &[u8] = &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+    let error =
+        serdine::shared::deserialize_with_object_table::<std::rc::Rc<MyStrongParentNode>, _>(
+            serialized_bytes,
+        )
+        .err()
+        .unwrap();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::UnresolvedObjectReference { id: 0 })
+    ));
+}
+
+#[test]
+fn test_deserialize_shared_references_truncated() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x0A,
+    ];
+
+    let error = serdine::shared::deserialize_with_object_table::<MyLevel, _>(serialized_bytes)
+        .err()
+        .unwrap();
+
+    assert_eq!(std::io::ErrorKind::UnexpectedEof, error.kind());
+}
+
+#[test]
+fn test_deserialize_shared_references_invalid_reference() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00,
+        0x05, 0x00, 0x00, 0x00,
+    ];
+
+    let error = serdine::shared::deserialize_with_object_table::<MyLevel, _>(serialized_bytes)
+        .err()
+        .unwrap();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::InvalidObjectReference { id: 5 })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// LIMITS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Deserialize)]
+pub struct MyListNode {
+    pub my_value: u8,
+    pub my_next: Option<Box<MyListNode>>,
+}
+
+// Nodes are stored as value, followed by the presence tag of the next one.
+//
+fn serialized_list(nodes_count: usize) -> Vec<u8> {
+    let mut serialized_bytes = vec![];
+
+    for node_index in 0..nodes_count {
+        let has_next = node_index < nodes_count - 1;
+        serialized_bytes.extend([node_index as u8, has_next as u8]);
+    }
+
+    serialized_bytes
+}
+
+#[test]
+fn test_deserialize_depth_limit() {
+    let options = serdine::Options::new().with_limits(serdine::Limits::new().with_max_depth(16));
+
+    let serialized_bytes = serialized_list(5);
+
+    let instance =
+        MyListNode::deserialize_with_options(serialized_bytes.as_slice(), &options).unwrap();
+
+    assert_eq!(0, instance.my_value);
+    assert_eq!(1, instance.my_next.unwrap().my_value);
+
+    let serialized_bytes = serialized_list(20);
+
+    let error = MyListNode::deserialize_with_options(serialized_bytes.as_slice(), &options)
+        .err()
+        .unwrap();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::DepthLimitExceeded { max_depth: 16 })
+    ));
+}
+
+#[test]
+fn test_deserialize_depth_limit_default() {
+    let serialized_bytes = serialized_list(100_000);
+
+    let error = MyListNode::deserialize(serialized_bytes.as_slice())
+        .err()
+        .unwrap();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::DepthLimitExceeded {
+            max_depth: serdine::limits::DEFAULT_MAX_DEPTH
+        })
+    ));
+}
+
+#[test]
+fn test_deserialize_allocation_limits() {
+    let options = serdine::Options::new()
+        .with_limits(serdine::Limits::new().with_max_len(3).with_max_alloc(16));
+
+    let serialized_bytes: &[u8] = &[0x03, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03];
+
+    let instance = Vec::<u8>::deserialize_with_options(serialized_bytes, &options).unwrap();
+
+    assert_eq!(vec![1, 2, 3], instance);
+
+    let serialized_bytes: &[u8] = &[0x04, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04];
+
+    let error = Vec::<u8>::deserialize_with_options(serialized_bytes, &options).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::LengthLimitExceeded { len: 4, max_len: 3 })
+    ));
+
+    // The budget is shared by all the collections; the outer one charges 3 * 24 bytes, and each
+    // inner one 2 * 4 bytes, so the last inner one exceeds it.
+    //
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x03, 0x00, 0x00, 0x00,
+        0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x02, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00,
+        0x02, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00,
+    ];
+
+    let options = serdine::Options::new()
+        .with_limits(serdine::Limits::new().with_max_alloc(3 * 24 + 2 * 2 * 4));
+
+    let error = Vec::<Vec<u32>>::deserialize_with_options(serialized_bytes, &options).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::AllocationLimitExceeded { .. })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// RUNTIME OPTIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Deserialize)]
+pub struct MyRuntimeOptionsStruct {
+    pub my_u32: u32,
+    pub my_u24: serdine::U24,
+    pub my_enum: MyEnum,
+    pub my_string: String,
+    #[serdine(endian = "little")]
+    pub my_little_endian_u16: u16,
+}
+
+#[test]
+fn test_deserialize_with_options() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0xCA, 0xFE, 0xBA, 0xBE,
+        0x01, 0x02, 0x03,
+        0xFF, 0xFE,
+        0x00, 0x00, 0x00, 0x02, 0x68, 0x69,
+        0x01, 0x02,
+    ];
+
+    let options = serdine::Options::new().with_endianness(serdine::Endianness::Big);
+
+    let instance =
+        MyRuntimeOptionsStruct::deserialize_with_options(serialized_bytes, &options).unwrap();
+
+    assert_eq!(0xCAFEBABE, instance.my_u32);
+    assert_eq!(0x010203, instance.my_u24.get());
+    assert_eq!(MyEnum::VarC, instance.my_enum);
+    assert_eq!("hi", instance.my_string);
+    assert_eq!(0x0201, instance.my_little_endian_u16);
+
+    // The options are restored afterwards.
+    //
+    assert_eq!(0x0201, u16::deserialize(&[0x01_u8, 0x02][..]).unwrap());
+}
+
+#[test]
+fn test_deserialize_with_options_policies() {
+    let invalid_string_bytes: &[u8] = &[0x02, 0x00, 0x00, 0x00, 0x68, 0xFF];
+
+    let error = String::deserialize(invalid_string_bytes).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::InvalidUtf8)
+    ));
+
+    let options = serdine::Options::new().with_string_policy(serdine::options::StringPolicy::Lossy);
+
+    let instance = String::deserialize_with_options(invalid_string_bytes, &options).unwrap();
+
+    assert_eq!("h\u{FFFD}", instance);
+
+    let options = serdine::Options::new().with_strict_bool(true);
+
+    let error = bool::deserialize_with_options(&[0xCA_u8][..], &options).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::InvalidBool { value: 0xCA })
+    ));
+
+    let options =
+        serdine::Options::new().with_trailing_bytes(serdine::options::TrailingBytes::Reject);
+
+    let error = u8::deserialize_with_options(&[0x01_u8, 0x02][..], &options).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::TrailingBytes)
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// BOOL LAYOUTS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Deserialize)]
+pub struct MyBoolLayoutsStruct {
+    pub my_bool: bool,
+    #[serdine(bool_layout = "u16")]
+    pub my_u16_bool: bool,
+    #[serdine(bool_layout = "u32")]
+    pub my_u32_bool: bool,
+    #[serdine(bool_layout = "u8_ff", strict_bool)]
+    pub my_ff_bool: bool,
+}
+
+#[test]
+fn test_deserialize_bool_layouts() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0xCA,
+        0x01, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0xFF,
+    ];
+
+    let instance = MyBoolLayoutsStruct::deserialize(serialized_bytes).unwrap();
+
+    assert!(instance.my_bool);
+    assert!(instance.my_u16_bool);
+    assert!(!instance.my_u32_bool);
+    assert!(instance.my_ff_bool);
+
+    // Strict fields reject values other than false/true.
+    //
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x00,
+        0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0x01,
+    ];
+
+    let error = MyBoolLayoutsStruct::deserialize(serialized_bytes)
+        .err()
+        .unwrap();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::InvalidBool { value: 0x01 })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// CONTEXT
+// ////////////////////////////////////////////////////////////////////////////////
+
+pub struct MyHeaderInfo {
+    pub version: u8,
+    pub palette_size: u8,
+}
+
+#[derive(Deserialize)]
+#[serdine(ctx = "MyHeaderInfo")]
+pub struct MyPalette {
+    #[serdine(count = "ctx.palette_size")]
+    pub my_colors: Vec<u8>,
+    #[deserialize = "deserialize_palette_flags"]
+    pub my_flags: u16,
+}
+
+// The flags have been introduced in version 2.
+//
+fn deserialize_palette_flags<R: std::io::Read>(
+    r: R,
+    ctx: &MyHeaderInfo,
+) -> Result<u16, std::io::Error> {
+    if ctx.version >= 2 {
+        u16::deserialize(r)
+    } else {
+        Ok(0)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MyFile {
+    pub my_version: u8,
+    pub my_palette_size: u8,
+    #[serdine(ctx = "MyHeaderInfo { version: *my_version, palette_size: *my_palette_size }")]
+    pub my_palette: MyPalette,
+}
+
+#[test]
+fn test_deserialize_with_ctx() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x02, 0x03,
+        0x0A, 0x0B, 0x0C,
+        0xFE, 0xCA,
+    ];
+
+    let instance = MyFile::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(2, instance.my_version);
+    assert_eq!(3, instance.my_palette_size);
+    assert_eq!(vec![0x0A, 0x0B, 0x0C], instance.my_palette.my_colors);
+    assert_eq!(0xCAFE, instance.my_palette.my_flags);
+
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x01, 0x02,
+        0x0A, 0x0B,
+    ];
+
+    let instance = MyFile::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(vec![0x0A, 0x0B], instance.my_palette.my_colors);
+    assert_eq!(0, instance.my_palette.my_flags);
+
+    let ctx = MyHeaderInfo {
+        version: 1,
+        palette_size: 1,
+    };
+
+    let instance =
+        <MyPalette as serdine::DeserializeWithCtx<_>>::deserialize_with_ctx(&[0x0A_u8][..], &ctx)
+            .unwrap();
+
+    assert_eq!(vec![0x0A], instance.my_colors);
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// CONDITIONAL FIELDS
+// ////////////////////////////////////////////////////////////////////////////////
+
+pub const MY_HAS_PALETTE: u8 = 0x01;
+
+#[derive(Deserialize)]
+pub struct MyConditionalStruct {
+    pub my_flags: u8,
+    #[serdine(if = "my_flags & MY_HAS_PALETTE != 0")]
+    pub my_palette: Option<[u8; 3]>,
+    pub my_u8: u8,
+}
+
+#[test]
+fn test_deserialize_conditional_fields() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x01,
+        0x0A, 0x0B, 0x0C,
+        0xCA,
+    ];
+
+    let instance = MyConditionalStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(0x01, instance.my_flags);
+    assert_eq!(Some([0x0A, 0x0B, 0x0C]), instance.my_palette);
+    assert_eq!(0xCA, instance.my_u8);
+
+    let serialized_bytes: &[u8] = &[0x00, 0xCA];
+
+    let instance = MyConditionalStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(None, instance.my_palette);
+    assert_eq!(0xCA, instance.my_u8);
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// VERSIONED STRUCTS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Deserialize)]
+#[serdine(version = "my_version")]
+pub struct MyVersionedStruct {
+    pub my_version: u8,
+    pub my_u16: u16,
+    #[serdine(since = 2, until = 3)]
+    pub my_legacy_u8: u8,
+    #[serdine(since = 3)]
+    pub my_u32: u32,
+}
+
+#[test]
+fn test_deserialize_versioned_struct() {
+    let serialized_bytes: &[u8] = &[0x01, 0xFE, 0xCA];
+
+    let instance = MyVersionedStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(1, instance.my_version);
+    assert_eq!(0xCAFE, instance.my_u16);
+    assert_eq!(0, instance.my_legacy_u8);
+    assert_eq!(0, instance.my_u32);
+
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x03,
+        0xFE, 0xCA,
+        0x80,
+        0xBE, 0xBA, 0xFE, 0xCA,
+    ];
+
+    let instance = MyVersionedStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(0x80, instance.my_legacy_u8);
+    assert_eq!(0xCAFEBABE, instance.my_u32);
+
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x04,
+        0xFE, 0xCA,
+        0xBE, 0xBA, 0xFE, 0xCA,
+    ];
+
+    let instance = MyVersionedStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(0, instance.my_legacy_u8);
+    assert_eq!(0xCAFEBABE, instance.my_u32);
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// MIGRATIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Deserialize, serdine_derive::Serialize)]
+pub struct MyRecordV1 {
+    pub my_u16: u16,
+}
+
+#[derive(Deserialize, serdine_derive::Serialize)]
+pub struct MyRecordV2 {
+    pub my_u16: u16,
+    pub my_u8: u8,
+}
+
+#[derive(Deserialize, serdine_derive::Serialize)]
+pub struct MyRecordV3 {
+    pub my_u32: u32,
+    pub my_u8: u8,
+}
+
+impl From<MyRecordV1> for MyRecordV2 {
+    fn from(previous: MyRecordV1) -> Self {
+        Self {
+            my_u16: previous.my_u16,
+            my_u8: 0,
+        }
+    }
+}
+
+impl From<MyRecordV2> for MyRecordV3 {
+    fn from(previous: MyRecordV2) -> Self {
+        Self {
+            my_u32: previous.my_u16 as u32,
+            my_u8: previous.my_u8,
+        }
+    }
+}
+
+impl serdine::migrate::Version for MyRecordV1 {
+    const VERSION: u64 = 1;
+    type Previous = Self;
+}
+
+impl serdine::migrate::Version for MyRecordV2 {
+    const VERSION: u64 = 2;
+    type Previous = MyRecordV1;
+}
+
+impl serdine::migrate::Version for MyRecordV3 {
+    const VERSION: u64 = 3;
+    type Previous = MyRecordV2;
+}
+
+#[test]
+fn test_deserialize_migrations() {
+    let serialized_bytes: &[u8] = &[0x01, 0xFE, 0xCA];
+
+    let instance =
+        serdine::migrate::deserialize_versioned::<u8, MyRecordV3, _>(serialized_bytes).unwrap();
+
+    assert_eq!(0xCAFE, instance.my_u32);
+    assert_eq!(0, instance.my_u8);
+
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x03,
+        0xBE, 0xBA, 0xFE, 0xCA,
+        0x80,
+    ];
+
+    let instance =
+        serdine::migrate::deserialize_versioned::<u8, MyRecordV3, _>(serialized_bytes).unwrap();
+
+    assert_eq!(0xCAFEBABE, instance.my_u32);
+    assert_eq!(0x80, instance.my_u8);
+
+    for version in [0, 4] {
+        let error = serdine::migrate::deserialize_version::<MyRecordV3, _>(&[][..], version)
+            .err()
+            .unwrap();
+
+        assert!(matches!(
+            error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+            Some(serdine::Error::UnknownVersion { version: error_version }) if *error_version == version
+        ));
+    }
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// DEFAULTS AT END OF INPUT
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Deserialize)]
+pub struct MyAppendedFieldsStruct {
+    pub my_u16: u16,
+    #[serdine(default)]
+    pub my_u32: u32,
+    #[serdine(default = "my_u16 as u64 * 2")]
+    pub my_u64: u64,
+}
+
+#[test]
+fn test_deserialize_defaults_at_eof() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x00, 0x01,
+        0xBE, 0xBA, 0xFE, 0xCA,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+    ];
+
+    let instance = MyAppendedFieldsStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(0x0100, instance.my_u16);
+    assert_eq!(0xCAFEBABE, instance.my_u32);
+    assert_eq!(0x0100_0000_0000_0000, instance.my_u64);
+
+    let serialized_bytes: &[u8] = &[0x00, 0x01];
+
+    let instance = MyAppendedFieldsStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(0, instance.my_u32);
+    assert_eq!(0x0200, instance.my_u64);
+
+    let serialized_bytes: &[u8] = &[0x00, 0x01, 0xBE, 0xBA, 0xFE, 0xCA];
+
+    let instance = MyAppendedFieldsStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(0xCAFEBABE, instance.my_u32);
+    assert_eq!(0x0200, instance.my_u64);
+
+    // The end of the input in the middle of a field is an error.
+    //
+    let serialized_bytes: &[u8] = &[0x00, 0x01, 0xBE, 0xBA];
+
+    let error = MyAppendedFieldsStruct::deserialize(serialized_bytes)
+        .err()
+        .unwrap();
+
+    assert_eq!(std::io::ErrorKind::UnexpectedEof, error.kind());
+}
+
+#[derive(Deserialize)]
+pub struct MyAppendedCollectionStruct {
+    pub my_count: u8,
+    #[serdine(default, count = "my_count")]
+    pub my_extra: Vec<u8>,
+    #[serdine(default)]
+    pub my_u32: u32,
+}
+
+// Fields that read zero bytes don't consume the following ones.
+//
+#[test]
+fn test_deserialize_defaults_at_eof_zero_length_field() {
+    let serialized_bytes: &[u8] = &[0x00, 0xBE, 0xBA, 0xFE, 0xCA];
+
+    let instance = MyAppendedCollectionStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(0, instance.my_count);
+    assert!(instance.my_extra.is_empty());
+    assert_eq!(0xCAFEBABE, instance.my_u32);
+
+    let serialized_bytes: &[u8] = &[0x02];
+
+    let instance = MyAppendedCollectionStruct::deserialize(serialized_bytes).unwrap();
+
+    assert!(instance.my_extra.is_empty());
+    assert_eq!(0, instance.my_u32);
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// ASSERTIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize)]
+#[serdine(
+    assert = "my_width >= my_height",
+    message = "Portrait images are not supported"
+)]
+pub struct MyImageHeader {
+    #[serdine(assert = "my_width <= 320", message = "Width out of range")]
+    pub my_width: u16,
+    #[serdine(assert = "my_height <= 200")]
+    pub my_height: u16,
+}
+
+#[test]
+fn test_deserialize_assertions() {
+    let serialized_bytes: &[u8] = &[0x40, 0x01, 0xC8, 0x00];
+
+    let instance = MyImageHeader::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(320, instance.my_width);
+    assert_eq!(200, instance.my_height);
+
+    let serialized_bytes: &[u8] = &[0x41, 0x01, 0xC8, 0x00];
+
+    let error = MyImageHeader::deserialize(serialized_bytes).unwrap_err();
+
+    match error.get_ref().unwrap().downcast_ref::<serdine::Error>() {
+        Some(serdine::Error::AssertionFailed {
+            field,
+            value,
+            message,
+        }) => {
+            assert_eq!(Some("my_width"), *field);
+            assert_eq!(Some("321"), value.as_deref());
+            assert_eq!("Width out of range", message);
+        }
+        _ => panic!("Unexpected error: {:?}", error),
+    }
+
+    // Without message, the assertion is reported.
+    //
+    let serialized_bytes: &[u8] = &[0x40, 0x01, 0xC9, 0x00];
+
+    let error = MyImageHeader::deserialize(serialized_bytes).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::AssertionFailed { field: Some("my_height"), message, .. }) if message == "my_height <= 200"
+    ));
+
+    let serialized_bytes: &[u8] = &[0x64, 0x00, 0x96, 0x00];
+
+    let error = MyImageHeader::deserialize(serialized_bytes).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::AssertionFailed { field: None, value: None, message }) if message == "Portrait images are not supported"
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// HOOKS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize)]
+#[serdine(post_deserialize = "Self::rebuild")]
+pub struct MyIndexedStruct {
+    pub my_values: [u8; 4],
+    pub my_max_index: u8,
+}
+
+impl MyIndexedStruct {
+    fn rebuild(&mut self) -> Result<(), std::io::Error> {
+        if self.my_max_index as usize >= self.my_values.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Index out of range",
+            ));
+        }
+
+        self.my_values.sort_unstable();
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_deserialize_hooks() {
+    let serialized_bytes: &[u8] = &[0x03, 0x01, 0x04, 0x02, 0x03];
+
+    let instance = MyIndexedStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!([0x01, 0x02, 0x03, 0x04], instance.my_values);
+
+    let serialized_bytes: &[u8] = &[0x03, 0x01, 0x04, 0x02, 0x04];
+
+    let error = MyIndexedStruct::deserialize(serialized_bytes).unwrap_err();
+
+    assert_eq!("Index out of range", error.to_string());
+}
+
+#[derive(Debug, Deserialize)]
+#[serdine(version = "my_version")]
+pub struct MyCheckedOptionalFieldsStruct {
+    pub my_version: u8,
+    #[serdine(since = 2, assert = "my_u16 != 0")]
+    pub my_u16: u16,
+    #[serdine(default, assert = "my_u8 != 0")]
+    pub my_u8: u8,
+}
+
+// Assertions are skipped for the fields that have not been read.
+//
+#[test]
+fn test_deserialize_assertions_on_fields_not_read() {
+    let serialized_bytes: &[u8] = &[0x01];
+
+    let instance = MyCheckedOptionalFieldsStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(1, instance.my_version);
+    assert_eq!(0, instance.my_u16);
+    assert_eq!(0, instance.my_u8);
+
+    let serialized_bytes: &[u8] = &[0x02, 0x00, 0x00];
+
+    let error = MyCheckedOptionalFieldsStruct::deserialize(serialized_bytes).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::AssertionFailed {
+            field: Some("my_u16"),
+            ..
+        })
+    ));
+
+    let serialized_bytes: &[u8] = &[0x02, 0x01, 0x00, 0x00];
+
+    let error = MyCheckedOptionalFieldsStruct::deserialize(serialized_bytes).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::AssertionFailed {
+            field: Some("my_u8"),
+            ..
+        })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// CALCULATED FIELDS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize)]
+pub struct MyCalculatedStruct {
+    #[serdine(calc = "self.my_items.len() as u16")]
+    pub my_count: u16,
+    #[serdine(calc = "my_items.iter().map(|item| *item as u32).sum()", verify)]
+    pub my_sum: u32,
+    #[serdine(count = "my_count")]
+    pub my_items: Vec<u8>,
+}
+
+#[test]
+fn test_deserialize_calculated_fields() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x02, 0x00,
+        0x90, 0x00, 0x00, 0x00,
+        0x40, 0x50,
+    ];
+
+    let instance = MyCalculatedStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(2, instance.my_count);
+    assert_eq!(0x90, instance.my_sum);
+    assert_eq!(vec![0x40, 0x50], instance.my_items);
+
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x02, 0x00,
+        0x91, 0x00, 0x00, 0x00,
+        0x40, 0x50,
+    ];
+
+    let error = MyCalculatedStruct::deserialize(serialized_bytes).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::CalculatedValueMismatch { field: "my_sum" })
+    ));
+}
+
+fn items_checksum(items: &[u8]) -> u8 {
+    items.iter().fold(0, |checksum, item| checksum ^ item)
+}
+
+// `self` as path segment refers to the module, not to the value.
+//
+#[derive(Debug, Deserialize)]
+pub struct MyCalculatedWithPathStruct {
+    #[serdine(len_prefix = "u8")]
+    pub my_items: Vec<u8>,
+    #[serdine(calc = "self::items_checksum(&self.my_items)", verify)]
+    pub my_checksum: u8,
+}
+
+#[test]
+fn test_deserialize_calculated_fields_with_self_path() {
+    let serialized_bytes: &[u8] = &[0x02, 0x0F, 0xF1, 0xFE];
+
+    let instance = MyCalculatedWithPathStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(vec![0x0F, 0xF1], instance.my_items);
+    assert_eq!(0xFE, instance.my_checksum);
+
+    let serialized_bytes: &[u8] = &[0x02, 0x0F, 0xF1, 0xFF];
+
+    let error = MyCalculatedWithPathStruct::deserialize(serialized_bytes).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::CalculatedValueMismatch {
+            field: "my_checksum"
+        })
+    ));
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MyCalculatedAppendedStruct {
+    pub my_u8: u8,
+    #[serdine(default, calc = "*my_u8 as u16 * 2", verify)]
+    pub my_double: u16,
+}
+
+// Verifications are skipped for the fields that have not been read.
+//
+#[test]
+fn test_deserialize_calculated_fields_not_read() {
+    let serialized_bytes: &[u8] = &[0x02];
+
+    let instance = MyCalculatedAppendedStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(2, instance.my_u8);
+    assert_eq!(0, instance.my_double);
+
+    let serialized_bytes: &[u8] = &[0x02, 0x05, 0x00];
+
+    let error = MyCalculatedAppendedStruct::deserialize(serialized_bytes).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::CalculatedValueMismatch { field: "my_double" })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// CONVERSIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize)]
+pub struct MyConvertedFieldsStruct {
+    #[serdine(as = "u16")]
+    pub my_len: usize,
+    #[serdine(as = "u16")]
+    pub my_u8: u8,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serdine(try_from = "u32")]
+pub struct MyTimeout(pub std::time::Duration);
+
+impl TryFrom<u32> for MyTimeout {
+    type Error = String;
+
+    fn try_from(millis: u32) -> Result<Self, Self::Error> {
+        if millis == 0 {
+            return Err("Zero timeout".to_string());
+        }
+
+        Ok(Self(std::time::Duration::from_millis(millis as u64)))
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serdine(from = "u8")]
+pub enum MyPriority {
+    Low,
+    High,
+}
+
+impl From<u8> for MyPriority {
+    fn from(value: u8) -> Self {
+        if value < 0x80 {
+            Self::Low
+        } else {
+            Self::High
+        }
+    }
+}
+
+#[test]
+fn test_deserialize_conversions() {
+    let serialized_bytes: &[u8] = &[0x34, 0x12, 0xFF, 0x00];
+
+    let instance = MyConvertedFieldsStruct::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(0x1234, instance.my_len);
+    assert_eq!(0xFF, instance.my_u8);
+
+    // Narrowing conversions are checked.
+    //
+    let serialized_bytes: &[u8] = &[0x34, 0x12, 0x00, 0x01];
+
+    let error = MyConvertedFieldsStruct::deserialize(serialized_bytes).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::ConversionFailed { .. })
+    ));
+
+    let instance = MyTimeout::deserialize(&[0xE8, 0x03, 0x00, 0x00][..]).unwrap();
+
+    assert_eq!(MyTimeout(std::time::Duration::from_secs(1)), instance);
+
+    let error = MyTimeout::deserialize(&[0x00, 0x00, 0x00, 0x00][..]).unwrap_err();
+
+    match error.get_ref().unwrap().downcast_ref::<serdine::Error>() {
+        Some(serdine::Error::ConversionFailed { source }) => {
+            assert_eq!("Zero timeout", source.to_string())
+        }
+        _ => panic!("Unexpected error: {:?}", error),
+    }
+
+    assert_eq!(
+        MyPriority::High,
+        MyPriority::deserialize(&[0xCA_u8][..]).unwrap()
+    );
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// TRANSPARENT TYPES
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serdine(transparent)]
+pub struct MyTileId(pub u16);
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serdine(transparent)]
+pub struct MyTileName {
+    pub my_name: String,
+}
+
+#[derive(Deserialize)]
+pub struct MyTile {
+    pub my_id: MyTileId,
+    pub my_name: MyTileName,
+}
+
+#[test]
+fn test_deserialize_transparent_types() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0xFE, 0xCA,
+        0x02, 0x00, 0x00, 0x00, 0x68, 0x69,
+    ];
+
+    let instance = MyTile::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(MyTileId(0xCAFE), instance.my_id);
+    assert_eq!("hi", instance.my_name.my_name);
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// REMOTE TYPES
+// ////////////////////////////////////////////////////////////////////////////////
+
+pub mod my_remote {
+    #[derive(Debug, PartialEq)]
+    pub struct MyPoint {
+        pub x: i16,
+        pub y: i16,
+    }
+}
+
+#[derive(Deserialize)]
+#[serdine(remote = "my_remote::MyPoint")]
+pub struct MyPointDef {
+    pub x: i16,
+    pub y: i16,
+}
+
+#[derive(Deserialize)]
+#[serdine(remote = "std::net::Ipv4Addr")]
+pub struct MyIpv4AddrDef {
+    #[serdine(getter = "std::net::Ipv4Addr::octets")]
+    pub octets: [u8; 4],
+}
+
+impl From<MyIpv4AddrDef> for std::net::Ipv4Addr {
+    fn from(value: MyIpv4AddrDef) -> Self {
+        Self::from(value.octets)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MyEndpoint {
+    #[serdine(with = "MyPointDef")]
+    pub my_point: my_remote::MyPoint,
+    #[serdine(with = "MyIpv4AddrDef")]
+    pub my_addr: std::net::Ipv4Addr,
+}
+
+#[test]
+fn test_deserialize_remote_types() {
+    #[rustfmt::skip]
+    let serialized_bytes: &[u8] = &[
+        0x01, 0x00, 0xFE, 0xFF,
+        0x7F, 0x00, 0x00, 0x01,
+    ];
+
+    let instance = MyEndpoint::deserialize(serialized_bytes).unwrap();
+
+    assert_eq!(my_remote::MyPoint { x: 1, y: -2 }, instance.my_point);
+    assert_eq!(std::net::Ipv4Addr::new(127, 0, 0, 1), instance.my_addr);
+}
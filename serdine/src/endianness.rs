@@ -0,0 +1,16 @@
+/// Byte order of multi-byte values.
+///
+/// Little endian is the default format of the trait implementations; big endian can be selected
+/// via `Options`, or per field (via the `endian` field attribute).
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+impl Default for Endianness {
+    fn default() -> Self {
+        Endianness::Little
+    }
+}
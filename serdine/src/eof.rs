@@ -0,0 +1,53 @@
+//! Detection of the end of the input at field boundaries.
+//!
+//! Fields with the `default` attribute take the default value when the input ends right before
+//! them (e.g. files written by older programs, before the fields were appended); an end of input in
+//! the middle of a field is still reported as `UnexpectedEof`.
+//!
+//! The input is not peeked: the field is deserialized via an `EofTracker`, and an `UnexpectedEof`
+//! before any byte has been read is considered the end of the input. This way, no byte is held
+//! back when a field reads zero bytes (e.g. an empty collection).
+
+use std::io::{ErrorKind, Read};
+
+/// Reader that tracks whether any byte has been read.
+///
+/// The inner reader is type-erased, so that recursive types don't instantiate the trait methods
+/// with ever-growing reader types.
+///
+pub struct EofTracker<'a> {
+    r: &'a mut dyn Read,
+    has_read: bool,
+}
+
+impl<'a> EofTracker<'a> {
+    pub fn new(r: &'a mut dyn Read) -> Self {
+        Self { r, has_read: false }
+    }
+
+    /// Returns None if the result is an `UnexpectedEof` raised before any byte has been read,
+    /// otherwise the result itself.
+    ///
+    pub fn unless_eof<T>(
+        &self,
+        result: Result<T, std::io::Error>,
+    ) -> Result<Option<T>, std::io::Error> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof && !self.has_read => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+impl Read for EofTracker<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, std::io::Error> {
+        let read_count = self.r.read(buf)?;
+
+        if read_count > 0 {
+            self.has_read = true;
+        }
+
+        Ok(read_count)
+    }
+}
@@ -0,0 +1,193 @@
+use std::fmt;
+
+/// Errors raised by serdine itself (as opposed to the I/O errors raised by readers/writers).
+///
+/// The trait signatures use `std::io::Error`, so these errors are wrapped into it; they can be
+/// retrieved via `std::io::Error::get_ref()` and `downcast_ref::<serdine::Error>()`.
+///
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// An integer value is out of range: on serialization, it doesn't fit the number of bytes it's
+    /// stored into; on deserialization, the stored value doesn't fit the target type. In both
+    /// cases, `width` is the stored width, in bytes.
+    IntegerOverflow { value: i128, width: usize },
+    /// A variable-length integer is encoded with redundant groups.
+    OverlongEncoding,
+    /// A variable-length integer doesn't fit the target type, or can't be represented by the
+    /// encoding (e.g. negative values in unsigned encodings).
+    VarintOutOfRange,
+    /// The bits of a bitfield don't represent a valid value for its type.
+    InvalidBitfieldValue { field: &'static str, bits: u64 },
+    /// A value doesn't fit the bits of its bitfield.
+    BitfieldOverflow { field: &'static str, width: u32 },
+    /// A flags value has bits that don't correspond to any declared flag.
+    UnknownFlagBits { type_name: &'static str },
+    /// A value doesn't correspond to any variant of an enum.
+    UnknownVariant {
+        type_name: &'static str,
+        value: i128,
+    },
+    /// A value is not a valid Unicode scalar value.
+    InvalidChar { value: u32 },
+    /// A zero value has been found for a non-zero type.
+    UnexpectedZero { type_name: &'static str },
+    /// A presence tag is neither the absent nor the present value.
+    InvalidPresenceTag { value: u64 },
+    /// A `Some` value has the same representation as `None`.
+    ReservedNoneValue,
+    /// The number of items of a collection doesn't match the declared count.
+    CountMismatch { expected: usize, actual: usize },
+    /// The declared count of a collection is not a valid length (e.g. it's negative).
+    InvalidCount,
+    /// An object table id doesn't refer to a value of the expected type.
+    InvalidObjectReference { id: u32 },
+    /// A strong pointer refers to a value that is still being deserialized.
+    UnresolvedObjectReference { id: u32 },
+    /// Weak pointers can be de/serialized only in object table mode.
+    ObjectTableRequired,
+    /// The nesting depth exceeds the configured limit.
+    DepthLimitExceeded { max_depth: usize },
+    /// The length of a collection exceeds the configured limit.
+    LengthLimitExceeded { len: usize, max_len: usize },
+    /// The memory allocated exceeds the configured budget.
+    AllocationLimitExceeded { max_alloc: usize },
+    /// A bool value is not valid (in strict mode).
+    InvalidBool { value: u32 },
+    /// A string is not valid UTF-8 (in strict mode).
+    InvalidUtf8,
+    /// The input has bytes following the deserialized value.
+    TrailingBytes,
+    /// The presence of a conditional value doesn't match its condition.
+    ConditionMismatch { condition: bool },
+    /// A version doesn't correspond to any version of the format.
+    UnknownVersion { version: u64 },
+    /// A value can't be converted to an older version.
+    DowngradeUnsupported { version: u64 },
+    /// A deserialized value doesn't satisfy an assertion; field assertions report the field name and
+    /// value (in `Debug` format).
+    AssertionFailed {
+        field: Option<&'static str>,
+        value: Option<String>,
+        message: String,
+    },
+    /// A calculated field doesn't match the value calculated from the other fields.
+    CalculatedValueMismatch { field: &'static str },
+    /// A value can't be converted from/to its stored type.
+    ConversionFailed {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IntegerOverflow { value, width } => {
+                write!(
+                    f,
+                    "Value {} out of range for a {}-byte stored integer",
+                    value, width
+                )
+            }
+            Error::OverlongEncoding => write!(f, "Overlong variable-length integer encoding"),
+            Error::VarintOutOfRange => write!(f, "Variable-length integer out of range"),
+            Error::InvalidBitfieldValue { field, bits } => {
+                write!(f, "Invalid value for bitfield `{}`: {:#b}", field, bits)
+            }
+            Error::BitfieldOverflow { field, width } => {
+                write!(
+                    f,
+                    "Value of bitfield `{}` doesn't fit into {} bit(s)",
+                    field, width
+                )
+            }
+            Error::UnknownFlagBits { type_name } => {
+                write!(f, "Unknown bits in `{}` flags", type_name)
+            }
+            Error::UnknownVariant { type_name, value } => {
+                write!(f, "Unknown value for `{}` variant: {}", type_name, value)
+            }
+            Error::InvalidChar { value } => write!(f, "Invalid char value: {:#x}", value),
+            Error::UnexpectedZero { type_name } => {
+                write!(f, "Unexpected zero value for `{}`", type_name)
+            }
+            Error::InvalidPresenceTag { value } => write!(f, "Invalid presence tag: {}", value),
+            Error::ReservedNoneValue => {
+                write!(f, "Value has the same representation as `None`")
+            }
+            Error::CountMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "Collection has {} item(s), but its count is {}",
+                    actual, expected
+                )
+            }
+            Error::InvalidCount => write!(f, "Invalid collection count"),
+            Error::InvalidObjectReference { id } => write!(f, "Invalid object reference: {}", id),
+            Error::UnresolvedObjectReference { id } => {
+                write!(f, "Object {} is referenced while being deserialized", id)
+            }
+            Error::ObjectTableRequired => {
+                write!(f, "Weak pointers require the object table mode")
+            }
+            Error::DepthLimitExceeded { max_depth } => {
+                write!(f, "Nesting depth exceeds the limit ({})", max_depth)
+            }
+            Error::LengthLimitExceeded { len, max_len } => {
+                write!(
+                    f,
+                    "Collection length {} exceeds the limit ({})",
+                    len, max_len
+                )
+            }
+            Error::AllocationLimitExceeded { max_alloc } => {
+                write!(f, "Allocations exceed the limit ({} bytes)", max_alloc)
+            }
+            Error::InvalidBool { value } => write!(f, "Invalid bool value: {:#x}", value),
+            Error::InvalidUtf8 => write!(f, "Invalid UTF-8 string"),
+            Error::TrailingBytes => write!(f, "Unexpected bytes after the value"),
+            Error::ConditionMismatch { condition } => {
+                write!(
+                    f,
+                    "Value presence doesn't match its condition (evaluated to {})",
+                    condition
+                )
+            }
+            Error::UnknownVersion { version } => write!(f, "Unknown version: {}", version),
+            Error::DowngradeUnsupported { version } => {
+                write!(f, "Downgrade to version {} not supported", version)
+            }
+            Error::AssertionFailed {
+                field,
+                value,
+                message,
+            } => match (field, value) {
+                (Some(field), Some(value)) => write!(
+                    f,
+                    "Assertion failed on field `{}` (value: {}): {}",
+                    field, value, message
+                ),
+                _ => write!(f, "Assertion failed: {}", message),
+            },
+            Error::CalculatedValueMismatch { field } => {
+                write!(f, "Field `{}` doesn't match its calculated value", field)
+            }
+            Error::ConversionFailed { source } => write!(f, "Conversion failed: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ConversionFailed { source } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for std::io::Error {
+    fn from(error: Error) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+    }
+}
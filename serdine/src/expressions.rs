@@ -0,0 +1,19 @@
+//! Support for the field attribute expressions; used by the derived implementations.
+//!
+//! The expressions evaluated on both deserialization and serialization (`count`, `if`, `ctx`,
+//! `version` and `calc`) see the fields as references, so that serialization doesn't need to copy
+//! them; method calls and most operators work as on values, thanks to auto-deref.
+//!
+//! The results of the `count`, `if` and `version` expressions are converted via `ExpressionValue`,
+//! so that they can be either values or references (e.g. `count = "my_len"`).
+
+/// Copies the value out of a reference, if any; invoked via method call syntax, so that auto-deref
+/// applies.
+///
+pub trait ExpressionValue: Copy {
+    fn expression_value(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Copy> ExpressionValue for T {}
@@ -0,0 +1,12 @@
+/// Types whose serialized size is statically known, e.g. in order to compute the offsets of
+/// fixed-size records within a file.
+///
+/// Derived (via `#[derive(FixedSize)]`) for enums, and for structs whose fields all have a fixed
+/// size; deriving it for structs with variable-length fields (e.g. a `Vec`, or a field with the
+/// `len_prefix` attribute) is a compile error.
+///
+pub trait FixedSize {
+    /// Serialized size, in bytes.
+    ///
+    const SIZE: usize;
+}
@@ -0,0 +1,78 @@
+//! Support for `bitflags` types (requires the `bitflags` feature).
+//!
+//! Flags are de/serialized as their underlying integer; bits that don't correspond to any declared
+//! flag are handled according to an `UnknownBits` policy. The functions can be used directly, or
+//! via the `bitflags` container attribute, which is applied to the flags type:
+//!
+//! ```ignore
+//! bitflags::bitflags! {
+//!     #[derive(Serialize, Deserialize)]
+//!     #[serdine(bitflags = "reject")]
+//!     pub struct MyFlags: u8 {
+//!         const A = 0b0001;
+//!         const B = 0b0010;
+//!     }
+//! }
+//! ```
+
+use std::io::{Read, Write};
+
+// Re-exported for the `FixedSize` derive; flags have the size of the underlying integer.
+//
+pub use bitflags::Flags;
+
+use crate::{Deserialize, Error, Serialize};
+
+/// Policy for bits that don't correspond to any declared flag.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnknownBits {
+    /// Fail with `Error::UnknownFlagBits`, both on de/serialization.
+    Reject,
+    /// Discard them, both on de/serialization.
+    Truncate,
+    /// Keep them as they are.
+    Retain,
+}
+
+pub fn deserialize<F, R>(r: R, unknown_bits: UnknownBits) -> Result<F, std::io::Error>
+where
+    F: Flags,
+    F::Bits: Deserialize,
+    R: Read,
+{
+    let bits = F::Bits::deserialize(r)?;
+
+    match unknown_bits {
+        UnknownBits::Reject => F::from_bits(bits).ok_or_else(|| unknown_flag_bits_error::<F>()),
+        UnknownBits::Truncate => Ok(F::from_bits_truncate(bits)),
+        UnknownBits::Retain => Ok(F::from_bits_retain(bits)),
+    }
+}
+
+pub fn serialize<F, W>(flags: &F, w: W, unknown_bits: UnknownBits) -> Result<(), std::io::Error>
+where
+    F: Flags,
+    F::Bits: Serialize,
+    W: Write,
+{
+    let bits = match unknown_bits {
+        UnknownBits::Reject => {
+            if F::from_bits(flags.bits()).is_none() {
+                return Err(unknown_flag_bits_error::<F>());
+            }
+            flags.bits()
+        }
+        UnknownBits::Truncate => flags.bits() & F::all().bits(),
+        UnknownBits::Retain => flags.bits(),
+    };
+
+    bits.serialize(w)
+}
+
+fn unknown_flag_bits_error<F>() -> std::io::Error {
+    Error::UnknownFlagBits {
+        type_name: std::any::type_name::<F>(),
+    }
+    .into()
+}
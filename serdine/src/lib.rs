@@ -13,12 +13,42 @@
   clippy::type_complexity,
 )]
 
+mod bit_order;
+pub mod bitfield;
+pub mod bits;
+pub mod boolean;
+pub mod collections;
+mod context;
+pub mod conversions;
 mod deserialize;
+mod endianness;
+pub mod eof;
+mod error;
+pub mod expressions;
+mod fixed_size;
+#[cfg(feature = "bitflags")]
+pub mod flags;
+pub mod limits;
 mod macros;
+pub mod migrate;
+pub mod odd_width;
+pub mod option;
+pub mod options;
 mod primitive_types;
 mod serialize;
+pub mod shared;
+mod std_types;
+pub mod varint;
 
+pub use bit_order::BitOrder;
+pub use context::{DeserializeWithCtx, SerializeWithCtx};
 pub use deserialize::Deserialize;
+pub use endianness::Endianness;
+pub use error::Error;
+pub use fixed_size::FixedSize;
+pub use limits::Limits;
+pub use odd_width::{I24, I40, I48, I56, U24, U40, U48, U56};
+pub use options::Options;
 pub use serialize::Serialize;
 
 pub use serdine_derive as derive;
@@ -0,0 +1,182 @@
+//! Limits for the deserialization of untrusted input.
+//!
+//! The limits apply to the current thread; they're set via the `Options` passed to
+//! `Deserialize::deserialize_with_options()`, and the defaults apply otherwise.
+//!
+//! The nesting depth is increased by each derived struct, and by each pointer and collection, so
+//! that corrupt or malicious input of recursive types fails with `Error::DepthLimitExceeded`
+//! instead of overflowing the stack. Manual implementations of recursive types can use
+//! `enter_nesting()` for the same purpose.
+//!
+//! Collections check their length against the per-collection maximum, and charge the memory of
+//! their items (`len * size_of::<T>()`) to the allocation budget of the deserialization, before
+//! deserializing the items, so that a corrupt length fails early with
+//! `Error::LengthLimitExceeded`/`Error::AllocationLimitExceeded`, instead of allocating large
+//! amounts of memory. Custom deserialization functions can use `check_collection_len()` and
+//! `charge_allocation()` for the same purpose. Both limits are unset by default.
+
+use std::cell::Cell;
+
+use crate::Error;
+
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    max_depth: usize,
+    // In bytes.
+    max_alloc: Option<usize>,
+    max_len: Option<usize>,
+}
+
+impl Limits {
+    pub const fn new() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_alloc: None,
+            max_len: None,
+        }
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Total budget, in bytes, of the memory allocated by the collections of a deserialization.
+    ///
+    pub fn with_max_alloc(mut self, max_alloc: usize) -> Self {
+        self.max_alloc = Some(max_alloc);
+        self
+    }
+
+    /// Maximum number of items of each collection.
+    ///
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    pub fn max_alloc(&self) -> Option<usize> {
+        self.max_alloc
+    }
+
+    pub fn max_len(&self) -> Option<usize> {
+        self.max_len
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+thread_local! {
+    static CURRENT_LIMITS: Cell<Limits> = const { Cell::new(Limits::new()) };
+    static CURRENT_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static ALLOCATED: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Runs the function with the given limits, and a new allocation budget, restoring the previous
+/// ones afterwards.
+///
+pub fn with_limits<F, U>(limits: &Limits, f: F) -> U
+where
+    F: FnOnce() -> U,
+{
+    let _guard = LimitsGuard {
+        previous_limits: CURRENT_LIMITS.with(|current| current.replace(*limits)),
+        previous_allocated: ALLOCATED.with(|allocated| allocated.replace(0)),
+    };
+
+    f()
+}
+
+pub fn current_limits() -> Limits {
+    CURRENT_LIMITS.with(Cell::get)
+}
+
+struct LimitsGuard {
+    previous_limits: Limits,
+    previous_allocated: usize,
+}
+
+impl Drop for LimitsGuard {
+    fn drop(&mut self) {
+        CURRENT_LIMITS.with(|current| current.set(self.previous_limits));
+        ALLOCATED.with(|allocated| allocated.set(self.previous_allocated));
+    }
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// NESTING DEPTH
+// ////////////////////////////////////////////////////////////////////////////////
+
+/// Increases the nesting depth, until the returned guard is dropped.
+///
+pub fn enter_nesting() -> Result<NestingGuard, std::io::Error> {
+    let max_depth = current_limits().max_depth;
+
+    CURRENT_DEPTH.with(|depth| {
+        if depth.get() >= max_depth {
+            return Err(Error::DepthLimitExceeded { max_depth }.into());
+        }
+
+        depth.set(depth.get() + 1);
+
+        Ok(NestingGuard { _private: () })
+    })
+}
+
+pub struct NestingGuard {
+    _private: (),
+}
+
+impl Drop for NestingGuard {
+    fn drop(&mut self) {
+        CURRENT_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// ALLOCATIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+pub fn check_collection_len(len: usize) -> Result<(), std::io::Error> {
+    match current_limits().max_len {
+        Some(max_len) if len > max_len => Err(Error::LengthLimitExceeded { len, max_len }.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Charges the given number of bytes to the allocation budget.
+///
+pub fn charge_allocation(bytes: usize) -> Result<(), std::io::Error> {
+    let max_alloc = current_limits().max_alloc;
+
+    ALLOCATED.with(|allocated| {
+        let new_allocated = allocated.get().saturating_add(bytes);
+
+        match max_alloc {
+            Some(max_alloc) if new_allocated > max_alloc => {
+                Err(Error::AllocationLimitExceeded { max_alloc }.into())
+            }
+            _ => {
+                allocated.set(new_allocated);
+                Ok(())
+            }
+        }
+    })
+}
+
+/// Checks the length of a collection of items of type `T`, and charges their memory.
+///
+pub fn charge_collection<T>(len: usize) -> Result<(), std::io::Error> {
+    check_collection_len(len)?;
+    charge_allocation(len.saturating_mul(std::mem::size_of::<T>()))
+}
@@ -5,17 +5,30 @@ macro_rules! impl_for_numeric {
                 fn deserialize<R: std::io::Read>(mut r: R) -> Result<Self, std::io::Error> {
                     let mut buffer = [0; std::mem::size_of::<$type>()];
                     r.read_exact(&mut buffer)?;
-                    let result = <$type>::from_le_bytes(buffer);
+                    let result = match crate::options::current_endianness() {
+                        crate::Endianness::Little => <$type>::from_le_bytes(buffer),
+                        crate::Endianness::Big => <$type>::from_be_bytes(buffer),
+                    };
                     Ok(result)
                 }
             }
         )+
     };
+    ( FixedSize, $( $type:ty ),+ ) => {
+        $(
+            impl crate::FixedSize for $type {
+                const SIZE: usize = std::mem::size_of::<$type>();
+            }
+        )+
+    };
     ( Serialize, $( $type:ty ),+ ) => {
         $(
             impl crate::Serialize for $type {
                 fn serialize<W: std::io::Write>(&self, mut w: W) -> Result<(), std::io::Error> {
-                    let encoded = self.to_le_bytes();
+                    let encoded = match crate::options::current_endianness() {
+                        crate::Endianness::Little => self.to_le_bytes(),
+                        crate::Endianness::Big => self.to_be_bytes(),
+                    };
                     w.write_all(&encoded)?;
                     Ok(())
                 }
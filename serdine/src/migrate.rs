@@ -0,0 +1,133 @@
+//! Migration between versions of a format, each represented by a distinct type.
+//!
+//! Each version implements `Version`, declaring its version number and its previous version, which
+//! is upgraded via `From`/`Into`; the first version declares itself as previous version.
+//!
+//! On deserialization, the type of the given version is deserialized, then upgraded step by step
+//! to the target type. On serialization, the value is downgraded step by step to the given version
+//! via `Version::downgrade()`, which is optional; versions that don't implement it can't be
+//! written in older formats (`Error::DowngradeUnsupported`).
+//!
+//! The version is either stored as a tag before the value (`deserialize_versioned()`/
+//! `serialize_versioned()`), or known from elsewhere, e.g. a file header (`deserialize_version()`/
+//! `serialize_version()`).
+
+use std::io::{Read, Write};
+
+use crate::{Deserialize, Error, Serialize};
+
+pub trait Version: Deserialize + Serialize {
+    const VERSION: u64;
+
+    /// The first version declares itself.
+    ///
+    type Previous: Version + Into<Self>;
+
+    /// Converts the value to the previous version; not supported by default.
+    ///
+    fn downgrade(&self) -> Option<Self::Previous> {
+        None
+    }
+}
+
+/// Types that can be used as version tag.
+///
+pub trait VersionTag: Deserialize + Serialize {
+    fn from_version(version: u64) -> Result<Self, std::io::Error>;
+
+    fn to_version(&self) -> u64;
+}
+
+macro_rules! impl_version_tag {
+    ( $( $type:ty ),+ ) => {
+        $(
+            impl VersionTag for $type {
+                fn from_version(version: u64) -> Result<Self, std::io::Error> {
+                    <$type>::try_from(version).map_err(|_| {
+                        Error::IntegerOverflow {
+                            value: version as i128,
+                            width: std::mem::size_of::<$type>(),
+                        }
+                        .into()
+                    })
+                }
+
+                fn to_version(&self) -> u64 {
+                    *self as u64
+                }
+            }
+        )+
+    };
+}
+
+impl_version_tag!(u8, u16, u32, u64);
+
+// ////////////////////////////////////////////////////////////////////////////////
+// DESERIALIZATION
+// ////////////////////////////////////////////////////////////////////////////////
+
+/// Deserializes the version tag, followed by the value of the corresponding version, upgraded to
+/// `T`.
+///
+pub fn deserialize_versioned<P, T, R>(mut r: R) -> Result<T, std::io::Error>
+where
+    P: VersionTag,
+    T: Version,
+    R: Read,
+{
+    let version = P::deserialize(&mut r)?.to_version();
+
+    deserialize_version(r, version)
+}
+
+/// Deserializes the value of the given version, upgraded to `T`.
+///
+pub fn deserialize_version<T, R>(r: R, version: u64) -> Result<T, std::io::Error>
+where
+    T: Version,
+    R: Read,
+{
+    if version == T::VERSION {
+        T::deserialize(r)
+    } else if version < T::VERSION && T::Previous::VERSION < T::VERSION {
+        deserialize_version::<T::Previous, R>(r, version).map(Into::into)
+    } else {
+        Err(Error::UnknownVersion { version }.into())
+    }
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// SERIALIZATION
+// ////////////////////////////////////////////////////////////////////////////////
+
+/// Serializes the version tag, followed by the value downgraded to the given version.
+///
+pub fn serialize_versioned<P, T, W>(value: &T, mut w: W, version: u64) -> Result<(), std::io::Error>
+where
+    P: VersionTag,
+    T: Version,
+    W: Write,
+{
+    P::from_version(version)?.serialize(&mut w)?;
+
+    serialize_version(value, w, version)
+}
+
+/// Serializes the value downgraded to the given version.
+///
+pub fn serialize_version<T, W>(value: &T, w: W, version: u64) -> Result<(), std::io::Error>
+where
+    T: Version,
+    W: Write,
+{
+    if version == T::VERSION {
+        value.serialize(w)
+    } else if version < T::VERSION && T::Previous::VERSION < T::VERSION {
+        match value.downgrade() {
+            Some(previous) => serialize_version(&previous, w, version),
+            None => Err(Error::DowngradeUnsupported { version }.into()),
+        }
+    } else {
+        Err(Error::UnknownVersion { version }.into())
+    }
+}
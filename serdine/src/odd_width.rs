@@ -0,0 +1,248 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{Read, Write};
+
+use crate::options::current_endianness;
+use crate::{Deserialize, Endianness, Error, FixedSize, Serialize};
+
+/// Integers that can be stored with an arbitrary width (from 1 to 8 bytes), which is what the
+/// `width`/`endian` field attributes are based on.
+///
+/// Signed values are sign-extended on deserialization; values that don't fit the target (on
+/// deserialization) or the width (on serialization) cause an `Error::IntegerOverflow`, which
+/// reports the stored width in both cases.
+///
+pub trait WidthInteger: Sized {
+    /// Natural width, in bytes.
+    const WIDTH: usize;
+    const SIGNED: bool;
+
+    fn to_i128(&self) -> i128;
+    fn from_i128(value: i128) -> Option<Self>;
+}
+
+macro_rules! impl_width_integer_for_primitive {
+    ( $( $type:ty => $signed:expr ),+ ) => {
+        $(
+            impl WidthInteger for $type {
+                const WIDTH: usize = std::mem::size_of::<$type>();
+                const SIGNED: bool = $signed;
+
+                fn to_i128(&self) -> i128 {
+                    *self as i128
+                }
+
+                fn from_i128(value: i128) -> Option<Self> {
+                    <$type>::try_from(value).ok()
+                }
+            }
+        )+
+    };
+}
+
+impl_width_integer_for_primitive!(
+    i8 => true, i16 => true, i32 => true, i64 => true,
+    u8 => false, u16 => false, u32 => false, u64 => false
+);
+
+pub fn deserialize_int<T: WidthInteger, R: Read>(
+    mut r: R,
+    width: usize,
+    endianness: Endianness,
+) -> Result<T, std::io::Error> {
+    let mut buffer = [0; 8];
+    let buffer = &mut buffer[..width];
+    r.read_exact(buffer)?;
+
+    let raw = assemble(buffer, endianness);
+
+    let value = if T::SIGNED {
+        sign_extend(raw, width) as i128
+    } else {
+        raw as i128
+    };
+
+    T::from_i128(value).ok_or_else(|| Error::IntegerOverflow { value, width }.into())
+}
+
+pub fn serialize_int<T: WidthInteger, W: Write>(
+    value: &T,
+    mut w: W,
+    width: usize,
+    endianness: Endianness,
+) -> Result<(), std::io::Error> {
+    let value = value.to_i128();
+    let bits = width as u32 * 8;
+
+    let (min, max) = if T::SIGNED {
+        (-(1_i128 << (bits - 1)), (1_i128 << (bits - 1)) - 1)
+    } else {
+        (0, (1_i128 << bits) - 1)
+    };
+
+    if value < min || value > max {
+        return Err(Error::IntegerOverflow { value, width }.into());
+    }
+
+    let mut buffer = [0; 8];
+    let buffer = &mut buffer[..width];
+    disassemble(value as u64, buffer, endianness);
+
+    w.write_all(buffer)
+}
+
+fn assemble(bytes: &[u8], endianness: Endianness) -> u64 {
+    let fold = |raw: u64, byte: &u8| raw << 8 | *byte as u64;
+
+    match endianness {
+        Endianness::Little => bytes.iter().rev().fold(0, fold),
+        Endianness::Big => bytes.iter().fold(0, fold),
+    }
+}
+
+fn disassemble(raw: u64, bytes: &mut [u8], endianness: Endianness) {
+    let width = bytes.len();
+
+    bytes.copy_from_slice(&raw.to_le_bytes()[..width]);
+
+    if endianness == Endianness::Big {
+        bytes.reverse();
+    }
+}
+
+fn sign_extend(raw: u64, width: usize) -> i64 {
+    let shift = 64 - width as u32 * 8;
+    (raw << shift) as i64 >> shift
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// ODD-WIDTH TYPES
+// ////////////////////////////////////////////////////////////////////////////////
+
+macro_rules! odd_width_integer {
+    ( $( $(#[$doc:meta])* $name:ident($inner:ty, $width:expr, $min:expr, $max:expr) ),+ $(,)? ) => {
+        $(
+            $(#[$doc])*
+            #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name($inner);
+
+            impl $name {
+                pub const BITS: u32 = $width * 8;
+                pub const MIN: Self = Self($min);
+                pub const MAX: Self = Self($max);
+
+                /// Returns None if the value is out of range.
+                pub fn new(value: $inner) -> Option<Self> {
+                    if (Self::MIN.0..=Self::MAX.0).contains(&value) {
+                        Some(Self(value))
+                    } else {
+                        None
+                    }
+                }
+
+                pub fn get(self) -> $inner {
+                    self.0
+                }
+
+                pub fn from_le_bytes(bytes: [u8; $width]) -> Self {
+                    Self::from_raw(assemble(&bytes, Endianness::Little))
+                }
+
+                pub fn from_be_bytes(bytes: [u8; $width]) -> Self {
+                    Self::from_raw(assemble(&bytes, Endianness::Big))
+                }
+
+                pub fn to_le_bytes(self) -> [u8; $width] {
+                    let mut bytes = [0; $width];
+                    disassemble(self.0 as u64, &mut bytes, Endianness::Little);
+                    bytes
+                }
+
+                pub fn to_be_bytes(self) -> [u8; $width] {
+                    let mut bytes = [0; $width];
+                    disassemble(self.0 as u64, &mut bytes, Endianness::Big);
+                    bytes
+                }
+
+                fn from_raw(raw: u64) -> Self {
+                    if <Self as WidthInteger>::SIGNED {
+                        Self(sign_extend(raw, $width) as $inner)
+                    } else {
+                        Self(raw as $inner)
+                    }
+                }
+            }
+
+            impl WidthInteger for $name {
+                const WIDTH: usize = $width;
+                const SIGNED: bool = <$inner as WidthInteger>::SIGNED;
+
+                fn to_i128(&self) -> i128 {
+                    self.0 as i128
+                }
+
+                fn from_i128(value: i128) -> Option<Self> {
+                    <$inner>::try_from(value).ok().and_then(Self::new)
+                }
+            }
+
+            impl From<$name> for $inner {
+                fn from(value: $name) -> Self {
+                    value.0
+                }
+            }
+
+            impl TryFrom<$inner> for $name {
+                type Error = Error;
+
+                fn try_from(value: $inner) -> Result<Self, Self::Error> {
+                    Self::new(value).ok_or(Error::IntegerOverflow {
+                        value: value as i128,
+                        width: $width,
+                    })
+                }
+            }
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Display::fmt(&self.0, f)
+                }
+            }
+
+            impl Deserialize for $name {
+                fn deserialize<R: Read>(r: R) -> Result<Self, std::io::Error> {
+                    deserialize_int(r, $width, current_endianness())
+                }
+            }
+
+            impl Serialize for $name {
+                fn serialize<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+                    serialize_int(self, w, $width, current_endianness())
+                }
+            }
+
+            impl FixedSize for $name {
+                const SIZE: usize = $width;
+            }
+        )+
+    };
+}
+
+odd_width_integer!(
+    /// Unsigned 24-bit integer.
+    U24(u32, 3, 0, 0xFF_FFFF),
+    /// Signed 24-bit integer.
+    I24(i32, 3, -0x80_0000, 0x7F_FFFF),
+    /// Unsigned 40-bit integer.
+    U40(u64, 5, 0, 0xFF_FFFF_FFFF),
+    /// Signed 40-bit integer.
+    I40(i64, 5, -0x80_0000_0000, 0x7F_FFFF_FFFF),
+    /// Unsigned 48-bit integer.
+    U48(u64, 6, 0, 0xFFFF_FFFF_FFFF),
+    /// Signed 48-bit integer.
+    I48(i64, 6, -0x8000_0000_0000, 0x7FFF_FFFF_FFFF),
+    /// Unsigned 56-bit integer.
+    U56(u64, 7, 0, 0xFF_FFFF_FFFF_FFFF),
+    /// Signed 56-bit integer.
+    I56(i64, 7, -0x80_0000_0000_0000, 0x7F_FFFF_FFFF_FFFF),
+);
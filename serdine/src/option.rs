@@ -0,0 +1,239 @@
+//! Encodings of `Option<T>`.
+//!
+//! The `Option` trait implementations use a `u8` presence tag (0 or 1) before the value; the other
+//! encodings are available as functions, which are what the field attributes are based on:
+//!
+//! - `presence = "u8|u16|u32|bool"`: a presence tag of the given type before the value; integer
+//!   tags must be 0 or 1, while `bool` tags treat any non-zero value as present;
+//! - `none_value = <value>`: a sentinel value represents `None` (e.g. `0xFFFF`); negative values
+//!   must be specified as strings (e.g. `"-1"`);
+//! - `none_if_zeroed`: a value whose bytes are all zero represents `None`; the value type must
+//!   implement `FixedSize`;
+//! - `if = "<expression>"`: the value is present if the condition holds, and nothing is stored
+//!   otherwise; the expression can refer to the previous fields, and to the context.
+//!
+//! With the sentinel-based encodings, `Some` values that have the same representation as `None`
+//! are rejected on serialization, as `Error::ReservedNoneValue`. With the condition-based encoding,
+//! values whose presence doesn't match the condition are rejected on serialization, as
+//! `Error::ConditionMismatch`.
+
+use std::io::{Read, Write};
+
+use crate::{Deserialize, Error, FixedSize, Serialize};
+
+// ////////////////////////////////////////////////////////////////////////////////
+// TRAIT IMPLEMENTATIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+impl<T: Deserialize> Deserialize for Option<T> {
+    fn deserialize<R: Read>(r: R) -> Result<Self, std::io::Error> {
+        deserialize_with_presence::<u8, T, R>(r)
+    }
+}
+
+impl<T: Serialize> Serialize for Option<T> {
+    fn serialize<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+        serialize_with_presence::<u8, T, W>(self, w)
+    }
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// PRESENCE TAG
+// ////////////////////////////////////////////////////////////////////////////////
+
+/// Types that can be used as presence tag.
+///
+pub trait PresenceTag: Deserialize + Serialize {
+    const ABSENT: Self;
+    const PRESENT: Self;
+
+    /// Returns None if the value is not a valid tag.
+    ///
+    fn is_present(&self) -> Option<bool>;
+
+    fn to_u64(&self) -> u64;
+}
+
+macro_rules! impl_presence_tag {
+    ( $( $type:ty ),+ ) => {
+        $(
+            impl PresenceTag for $type {
+                const ABSENT: Self = 0;
+                const PRESENT: Self = 1;
+
+                fn is_present(&self) -> Option<bool> {
+                    match *self {
+                        0 => Some(false),
+                        1 => Some(true),
+                        _ => None,
+                    }
+                }
+
+                fn to_u64(&self) -> u64 {
+                    *self as u64
+                }
+            }
+        )+
+    };
+}
+
+impl_presence_tag!(u8, u16, u32);
+
+impl PresenceTag for bool {
+    const ABSENT: Self = false;
+    const PRESENT: Self = true;
+
+    fn is_present(&self) -> Option<bool> {
+        Some(*self)
+    }
+
+    fn to_u64(&self) -> u64 {
+        *self as u64
+    }
+}
+
+pub fn deserialize_with_presence<P, T, R>(mut r: R) -> Result<Option<T>, std::io::Error>
+where
+    P: PresenceTag,
+    T: Deserialize,
+    R: Read,
+{
+    let tag = P::deserialize(&mut r)?;
+
+    match tag.is_present() {
+        Some(true) => T::deserialize(r).map(Some),
+        Some(false) => Ok(None),
+        None => Err(Error::InvalidPresenceTag {
+            value: tag.to_u64(),
+        }
+        .into()),
+    }
+}
+
+pub fn serialize_with_presence<P, T, W>(value: &Option<T>, mut w: W) -> Result<(), std::io::Error>
+where
+    P: PresenceTag,
+    T: Serialize,
+    W: Write,
+{
+    match value {
+        Some(value) => {
+            P::PRESENT.serialize(&mut w)?;
+            value.serialize(w)
+        }
+        None => P::ABSENT.serialize(w),
+    }
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// SENTINEL VALUE
+// ////////////////////////////////////////////////////////////////////////////////
+
+pub fn deserialize_with_none_value<T, R>(r: R, none_value: T) -> Result<Option<T>, std::io::Error>
+where
+    T: Deserialize + PartialEq,
+    R: Read,
+{
+    let value = T::deserialize(r)?;
+
+    if value == none_value {
+        Ok(None)
+    } else {
+        Ok(Some(value))
+    }
+}
+
+pub fn serialize_with_none_value<T, W>(
+    value: &Option<T>,
+    w: W,
+    none_value: T,
+) -> Result<(), std::io::Error>
+where
+    T: Serialize + PartialEq,
+    W: Write,
+{
+    match value {
+        Some(value) if *value == none_value => Err(Error::ReservedNoneValue.into()),
+        Some(value) => value.serialize(w),
+        None => none_value.serialize(w),
+    }
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// ZEROED VALUE
+// ////////////////////////////////////////////////////////////////////////////////
+
+/// The `T::SIZE` bytes are checked before being deserialized, so that `None` is read back also for
+/// types that reject zero values (e.g. `NonZeroU16`).
+///
+pub fn deserialize_if_zeroed<T, R>(mut r: R) -> Result<Option<T>, std::io::Error>
+where
+    T: Deserialize + FixedSize,
+    R: Read,
+{
+    let mut buffer = vec![0; T::SIZE];
+
+    r.read_exact(&mut buffer)?;
+
+    if buffer.iter().all(|byte| *byte == 0) {
+        Ok(None)
+    } else {
+        T::deserialize(buffer.as_slice()).map(Some)
+    }
+}
+
+/// `None` is written as `T::SIZE` zero bytes. `Some` values whose bytes are all zero are rejected
+/// before anything is written, since they can't be told apart from `None`.
+///
+pub fn serialize_if_zeroed<T, W>(value: &Option<T>, mut w: W) -> Result<(), std::io::Error>
+where
+    T: Serialize + FixedSize,
+    W: Write,
+{
+    match value {
+        Some(value) => {
+            let mut buffer = Vec::with_capacity(T::SIZE);
+
+            value.serialize(&mut buffer)?;
+
+            if buffer.iter().all(|byte| *byte == 0) {
+                return Err(Error::ReservedNoneValue.into());
+            }
+
+            w.write_all(&buffer)
+        }
+        None => std::io::copy(&mut std::io::repeat(0).take(T::SIZE as u64), &mut w).map(|_| ()),
+    }
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// CONDITION
+// ////////////////////////////////////////////////////////////////////////////////
+
+pub fn deserialize_with_condition<T, R>(r: R, condition: bool) -> Result<Option<T>, std::io::Error>
+where
+    T: Deserialize,
+    R: Read,
+{
+    if condition {
+        T::deserialize(r).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn serialize_with_condition<T, W>(
+    value: &Option<T>,
+    w: W,
+    condition: bool,
+) -> Result<(), std::io::Error>
+where
+    T: Serialize,
+    W: Write,
+{
+    match value {
+        Some(value) if condition => value.serialize(w),
+        None if !condition => Ok(()),
+        _ => Err(Error::ConditionMismatch { condition }.into()),
+    }
+}
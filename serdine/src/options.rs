@@ -0,0 +1,168 @@
+//! Runtime de/serialization options.
+//!
+//! The options apply to the current thread; they're set via
+//! `Deserialize::deserialize_with_options()`/`Serialize::serialize_with_options()`, and the defaults
+//! apply otherwise. Field attributes (e.g. `endian`) take precedence over the options.
+
+use std::cell::Cell;
+use std::io::Read;
+
+use crate::limits::{self, Limits};
+use crate::{Endianness, Error};
+
+/// Handling of invalid UTF-8 in strings, on deserialization.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StringPolicy {
+    /// Fail with `Error::InvalidUtf8`.
+    Strict,
+    /// Replace invalid sequences with `U+FFFD`.
+    Lossy,
+}
+
+/// Handling of the bytes following the value, on deserialization.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrailingBytes {
+    Allow,
+    /// Fail with `Error::TrailingBytes`.
+    Reject,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    endianness: Endianness,
+    string_policy: StringPolicy,
+    strict_bool: bool,
+    limits: Limits,
+    trailing_bytes: TrailingBytes,
+}
+
+impl Options {
+    pub const fn new() -> Self {
+        Self {
+            endianness: Endianness::Little,
+            string_policy: StringPolicy::Strict,
+            strict_bool: false,
+            limits: Limits::new(),
+            trailing_bytes: TrailingBytes::Allow,
+        }
+    }
+
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
+    pub fn with_string_policy(mut self, string_policy: StringPolicy) -> Self {
+        self.string_policy = string_policy;
+        self
+    }
+
+    /// When enabled, bool values other than 0 and 1 fail with `Error::InvalidBool`, instead of
+    /// being considered true.
+    ///
+    pub fn with_strict_bool(mut self, strict_bool: bool) -> Self {
+        self.strict_bool = strict_bool;
+        self
+    }
+
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_trailing_bytes(mut self, trailing_bytes: TrailingBytes) -> Self {
+        self.trailing_bytes = trailing_bytes;
+        self
+    }
+
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    pub fn string_policy(&self) -> StringPolicy {
+        self.string_policy
+    }
+
+    pub fn strict_bool(&self) -> bool {
+        self.strict_bool
+    }
+
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    pub fn trailing_bytes(&self) -> TrailingBytes {
+        self.trailing_bytes
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+thread_local! {
+    static CURRENT_OPTIONS: Cell<Options> = const { Cell::new(Options::new()) };
+    // Kept separately, so that reading it doesn't copy the whole options on each primitive value.
+    static CURRENT_ENDIANNESS: Cell<Endianness> = const { Cell::new(Options::new().endianness) };
+}
+
+/// Runs the function with the given options (including the limits), restoring the previous ones
+/// afterwards.
+///
+pub fn with_options<F, U>(options: &Options, f: F) -> U
+where
+    F: FnOnce() -> U,
+{
+    let _guard = OptionsGuard {
+        previous_options: CURRENT_OPTIONS.with(|current| current.replace(*options)),
+        previous_endianness: CURRENT_ENDIANNESS.with(|current| current.replace(options.endianness)),
+    };
+
+    limits::with_limits(&options.limits, f)
+}
+
+pub fn current_options() -> Options {
+    CURRENT_OPTIONS.with(Cell::get)
+}
+
+/// Equivalent to `current_options().endianness()`, without copying the options; used by the
+/// primitive types and the derived implementations.
+///
+pub fn current_endianness() -> Endianness {
+    CURRENT_ENDIANNESS.with(Cell::get)
+}
+
+struct OptionsGuard {
+    previous_options: Options,
+    previous_endianness: Endianness,
+}
+
+impl Drop for OptionsGuard {
+    fn drop(&mut self) {
+        CURRENT_OPTIONS.with(|current| current.set(self.previous_options));
+        CURRENT_ENDIANNESS.with(|current| current.set(self.previous_endianness));
+    }
+}
+
+/// Applies the trailing bytes policy of the current options to the reader.
+///
+pub fn check_trailing_bytes<R: Read>(mut r: R) -> Result<(), std::io::Error> {
+    if current_options().trailing_bytes == TrailingBytes::Allow {
+        return Ok(());
+    }
+
+    let mut buffer = [0; 1];
+
+    loop {
+        match r.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(_) => return Err(Error::TrailingBytes.into()),
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use std::convert::TryInto;
 
+use crate::boolean::{self, BoolLayout};
 use crate::macros::impl_for_numeric;
-use crate::{Deserialize, Serialize};
+use crate::{Deserialize, FixedSize, Serialize};
 
 impl_for_numeric!(
     Deserialize,
@@ -21,12 +22,11 @@ impl_for_numeric!(
 
 impl_for_numeric!(Serialize, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
 
+impl_for_numeric!(FixedSize, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
+
 impl Deserialize for bool {
-    fn deserialize<R: std::io::Read>(mut r: R) -> Result<Self, std::io::Error> {
-        let mut buffer = [0; 1];
-        r.read_exact(&mut buffer)?;
-        let result = buffer[0] != 0;
-        Ok(result)
+    fn deserialize<R: std::io::Read>(r: R) -> Result<Self, std::io::Error> {
+        boolean::deserialize(r, BoolLayout::U8, false)
     }
 }
 
@@ -38,6 +38,10 @@ impl Serialize for bool {
     }
 }
 
+impl FixedSize for bool {
+    const SIZE: usize = 1;
+}
+
 impl<T, const N: usize> Deserialize for [T; N]
 where
     T: Deserialize,
@@ -78,3 +82,10 @@ where
         Ok(())
     }
 }
+
+impl<T, const N: usize> FixedSize for [T; N]
+where
+    T: FixedSize,
+{
+    const SIZE: usize = N * T::SIZE;
+}
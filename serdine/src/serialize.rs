@@ -1,5 +1,17 @@
 use std::io::Write;
 
+use crate::options::{self, Options};
+
 pub trait Serialize {
     fn serialize<W: Write>(&self, w: W) -> Result<(), std::io::Error>;
+
+    /// Serializes with the given options, instead of the default ones.
+    ///
+    fn serialize_with_options<W: Write>(
+        &self,
+        w: W,
+        options: &Options,
+    ) -> Result<(), std::io::Error> {
+        options::with_options(options, || self.serialize(w))
+    }
 }
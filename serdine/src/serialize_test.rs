@@ -1,6 +1,6 @@
 use crate as serdine;
 use crate::Serialize as DeserializeDisambiguate;
-use serdine_derive::Serialize;
+use serdine_derive::{BitSerialize, FixedSize, Serialize};
 
 // ////////////////////////////////////////////////////////////////////////////////
 // STRUCT WITH NAMED FIELDS
@@ -61,7 +61,7 @@ fn test_serialize_named_fields_struct() {
 
 #[derive(Serialize)]
 #[repr(u16)]
-enum MyEnum {
+pub enum MyEnum {
     VarA = 0,
     VarB = 1,
     VarC = 65534,
@@ -84,3 +84,1412 @@ fn test_serialize_enum() {
 
     assert_eq!(expected_bytes, serialized_instance);
 }
+
+// ////////////////////////////////////////////////////////////////////////////////
+// ODD-WIDTH INTEGERS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Serialize)]
+pub struct MyOddWidthStruct {
+    pub my_u24: serdine::U24,
+    pub my_i24: serdine::I24,
+    #[serdine(endian = "big")]
+    pub my_u48_be: serdine::U48,
+    #[serdine(width = 3)]
+    pub my_u32: u32,
+    #[serdine(width = 3, endian = "big")]
+    pub my_i32_be: i32,
+}
+
+#[test]
+fn test_serialize_odd_width_integers() {
+    let instance = MyOddWidthStruct {
+        my_u24: serdine::U24::new(0x010203).unwrap(),
+        my_i24: serdine::I24::new(-2).unwrap(),
+        my_u48_be: serdine::U48::new(0x010203040506).unwrap(),
+        my_u32: 0xFEBABE,
+        my_i32_be: -3,
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0x03, 0x02, 0x01,
+        0xFE, 0xFF, 0xFF,
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+        0xBE, 0xBA, 0xFE,
+        0xFF, 0xFF, 0xFD,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+}
+
+#[test]
+fn test_serialize_odd_width_integer_overflow() {
+    let instance = MyOddWidthStruct {
+        my_u24: serdine::U24::MAX,
+        my_i24: serdine::I24::MIN,
+        my_u48_be: serdine::U48::MAX,
+        my_u32: 0x01000000,
+        my_i32_be: 0,
+    };
+
+    let error = instance.serialize(Vec::new()).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::IntegerOverflow {
+            value: 0x01000000,
+            width: 3
+        })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// VARIABLE-LENGTH INTEGERS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Serialize)]
+pub struct MyVarintStruct {
+    #[serdine(encoding = "uleb128")]
+    pub my_uleb128: u32,
+    #[serdine(encoding = "sleb128")]
+    pub my_sleb128: i32,
+    #[serdine(encoding = "zigzag")]
+    pub my_zigzag: i64,
+    #[serdine(encoding = "vlq")]
+    pub my_vlq: u32,
+    pub my_wrapped_uleb128: serdine::varint::Uleb128<u8>,
+}
+
+#[test]
+fn test_serialize_varints() {
+    let instance = MyVarintStruct {
+        my_uleb128: 624485,
+        my_sleb128: -123456,
+        my_zigzag: -2,
+        my_vlq: 0x4000,
+        my_wrapped_uleb128: serdine::varint::Uleb128(0x7F),
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0xE5, 0x8E, 0x26,
+        0xC0, 0xBB, 0x78,
+        0x03,
+        0x81, 0x80, 0x00,
+        0x7F,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// BITFIELDS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Serialize)]
+#[repr(u8)]
+enum MyBitsEnum {
+    #[allow(dead_code)]
+    VarA = 0,
+    VarB = 1,
+    // Suffixed discriminants are supported as well.
+    VarC = 2u8,
+}
+
+#[derive(Serialize)]
+#[serdine(bitfield = "u16")]
+pub struct MyBitfield {
+    #[serdine(bits = 3)]
+    pub my_u8: u8,
+    #[serdine(bits = 1)]
+    pub my_bool: bool,
+    #[serdine(bits = 2)]
+    my_enum: MyBitsEnum,
+    #[serdine(bits = 4)]
+    pub my_i8: i8,
+}
+
+#[derive(Serialize)]
+#[serdine(bitfield = "u8", bit_order = "msb")]
+pub struct MyMsbFirstBitfield {
+    #[serdine(bits = 1)]
+    pub my_bool: bool,
+    #[serdine(bits = 3)]
+    pub my_u8: u8,
+}
+
+#[test]
+fn test_serialize_bitfields() {
+    let mut serialized_instance = Vec::new();
+
+    MyBitfield {
+        my_u8: 5,
+        my_bool: true,
+        my_enum: MyBitsEnum::VarC,
+        my_i8: -3,
+    }
+    .serialize(&mut serialized_instance)
+    .unwrap();
+
+    MyMsbFirstBitfield {
+        my_bool: true,
+        my_u8: 5,
+    }
+    .serialize(&mut serialized_instance)
+    .unwrap();
+
+    let expected_bytes: &[u8] = &[0x6D, 0x03, 0xD0];
+
+    assert_eq!(expected_bytes, serialized_instance);
+}
+
+#[test]
+fn test_serialize_bitfield_overflow() {
+    let instance = MyBitfield {
+        my_u8: 8,
+        my_bool: false,
+        my_enum: MyBitsEnum::VarB,
+        my_i8: 0,
+    };
+
+    let error = instance.serialize(Vec::new()).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::BitfieldOverflow {
+            field: "my_u8",
+            width: 3
+        })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// BIT-LEVEL STRUCTS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(BitSerialize)]
+pub struct MyBitLevelStruct {
+    #[serdine(bits = 3)]
+    pub my_u8: u8,
+    pub my_bool: bool,
+    #[serdine(bits = 11)]
+    pub my_u16: u16,
+    #[serdine(align)]
+    pub my_aligned_u8: u8,
+}
+
+#[derive(Serialize)]
+pub struct MyMixedLevelStruct {
+    pub my_u8: u8,
+    #[serdine(bit_order = "msb")]
+    pub my_bits: MyBitLevelStruct,
+    pub my_u16: u16,
+}
+
+#[test]
+fn test_serialize_bit_level_struct() {
+    let instance = MyMixedLevelStruct {
+        my_u8: 0x11,
+        my_bits: MyBitLevelStruct {
+            my_u8: 5,
+            my_bool: true,
+            my_u16: 0x55E,
+            my_aligned_u8: 0x7F,
+        },
+        my_u16: 0x1234,
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0x11,
+        0xBA, 0xBC, 0x7F,
+        0x34, 0x12,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+}
+
+#[test]
+fn test_bit_writer_lsb_first() {
+    let mut writer = serdine::bits::BitWriter::new(Vec::new(), serdine::BitOrder::LsbFirst);
+
+    writer.write_bits(0b100, 3).unwrap();
+    writer.write_bits(0b1_0101, 5).unwrap();
+    writer.write_bit(true).unwrap();
+
+    let error = writer.write_bits(0, 65).err().unwrap();
+
+    assert_eq!(std::io::ErrorKind::InvalidInput, error.kind());
+
+    let expected_bytes: &[u8] = &[0b1010_1100, 0b0000_0001];
+
+    assert_eq!(expected_bytes, writer.finish().unwrap());
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// BITFLAGS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "bitflags")]
+bitflags::bitflags! {
+    #[derive(Serialize)]
+    #[serdine(bitflags = "reject")]
+    pub struct MyRejectingFlags: u8 {
+        const FLAG_A = 0b01;
+        const FLAG_B = 0b10;
+    }
+
+    #[derive(Serialize)]
+    #[serdine(bitflags = "truncate")]
+    pub struct MyTruncatingFlags: u8 {
+        const FLAG_A = 0b01;
+        const FLAG_B = 0b10;
+    }
+}
+
+#[cfg(feature = "bitflags")]
+#[test]
+fn test_serialize_bitflags() {
+    let mut serialized_instance = Vec::new();
+
+    MyRejectingFlags::FLAG_B
+        .serialize(&mut serialized_instance)
+        .unwrap();
+    MyTruncatingFlags::from_bits_retain(0x81)
+        .serialize(&mut serialized_instance)
+        .unwrap();
+
+    let expected_bytes: &[u8] = &[0x02, 0x01];
+
+    assert_eq!(expected_bytes, serialized_instance);
+
+    let error = MyRejectingFlags::from_bits_retain(0x81)
+        .serialize(Vec::new())
+        .unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::UnknownFlagBits { .. })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// STANDARD LIBRARY TYPES
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Serialize)]
+pub struct MyStdTypesStruct {
+    pub my_tuple: (u8, i16),
+    pub my_char: char,
+    pub my_non_zero: std::num::NonZeroU16,
+    pub my_wrapping: std::num::Wrapping<u8>,
+    pub my_usize: usize,
+    pub my_box: Box<u16>,
+    pub my_cell: std::cell::Cell<u8>,
+}
+
+#[test]
+fn test_serialize_std_types() {
+    let instance = MyStdTypesStruct {
+        my_tuple: (1, -2),
+        my_char: 'A',
+        my_non_zero: std::num::NonZeroU16::new(2).unwrap(),
+        my_wrapping: std::num::Wrapping(3),
+        my_usize: 4,
+        my_box: Box::new(5),
+        my_cell: std::cell::Cell::new(6),
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0x01, 0xFE, 0xFF,
+        0x41, 0x00, 0x00, 0x00,
+        0x02, 0x00,
+        0x03,
+        0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x05, 0x00,
+        0x06,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// OPTIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Serialize)]
+pub struct MyOptionsStruct {
+    pub my_default: Option<u8>,
+    #[serdine(presence = "u16")]
+    pub my_presence: Option<u16>,
+    #[serdine(none_value = 0xFFFF)]
+    pub my_sentinel: Option<u16>,
+    #[serdine(none_value = "-1")]
+    pub my_negative_sentinel: Option<i32>,
+    #[serdine(none_if_zeroed)]
+    pub my_zeroed: Option<[u8; 3]>,
+}
+
+#[test]
+fn test_serialize_options() {
+    let instance = MyOptionsStruct {
+        my_default: Some(5),
+        my_presence: Some(6),
+        my_sentinel: Some(7),
+        my_negative_sentinel: Some(8),
+        my_zeroed: Some([0x00, 0x09, 0x00]),
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0x01, 0x05,
+        0x01, 0x00, 0x06, 0x00,
+        0x07, 0x00,
+        0x08, 0x00, 0x00, 0x00,
+        0x00, 0x09, 0x00,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+
+    let instance = MyOptionsStruct {
+        my_default: None,
+        my_presence: None,
+        my_sentinel: None,
+        my_negative_sentinel: None,
+        my_zeroed: None,
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0x00,
+        0x00, 0x00,
+        0xFF, 0xFF,
+        0xFF, 0xFF, 0xFF, 0xFF,
+        0x00, 0x00, 0x00,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+}
+
+#[test]
+fn test_serialize_options_reserved_none_value() {
+    let instance = MyOptionsStruct {
+        my_default: None,
+        my_presence: None,
+        my_sentinel: Some(0xFFFF),
+        my_negative_sentinel: None,
+        my_zeroed: None,
+    };
+
+    let error = instance.serialize(Vec::new()).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::ReservedNoneValue)
+    ));
+}
+
+#[test]
+fn test_serialize_options_reserved_zeroed_value() {
+    let value = Some([0x00_u8, 0x00, 0x00]);
+
+    let mut serialized_instance = Vec::new();
+
+    let error = serdine::option::serialize_if_zeroed(&value, &mut serialized_instance).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::ReservedNoneValue)
+    ));
+
+    // The value is checked before anything is written.
+    //
+    assert!(serialized_instance.is_empty());
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// COLLECTIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Serialize)]
+pub struct MyCollectionsStruct {
+    pub my_vec: Vec<u16>,
+    #[serdine(len_prefix = "u8")]
+    pub my_deque: std::collections::VecDeque<u8>,
+    pub my_len: u16,
+    #[serdine(count = "my_len")]
+    pub my_set: std::collections::BTreeSet<u8>,
+    #[serdine(len_prefix = "u16")]
+    pub my_map: std::collections::HashMap<u8, u16>,
+    pub my_hash_set: std::collections::HashSet<u8>,
+}
+
+#[test]
+fn test_serialize_collections() {
+    let instance = MyCollectionsStruct {
+        my_vec: vec![1, 2],
+        my_deque: std::collections::VecDeque::from([3]),
+        my_len: 2,
+        my_set: std::collections::BTreeSet::from([5, 4]),
+        my_map: std::collections::HashMap::from([(8, 9), (6, 7), (0xFF, 0x0B)]),
+        my_hash_set: std::collections::HashSet::from([0x0D, 0x0A, 0x0C]),
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    // Hash collections are sorted by key.
+    //
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00,
+        0x01, 0x03,
+        0x02, 0x00,
+        0x04, 0x05,
+        0x03, 0x00, 0x06, 0x07, 0x00, 0x08, 0x09, 0x00, 0xFF, 0x0B, 0x00,
+        0x03, 0x00, 0x00, 0x00, 0x0A, 0x0C, 0x0D,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+}
+
+#[test]
+fn test_serialize_collections_errors() {
+    let instance = MyCollectionsStruct {
+        my_vec: vec![],
+        my_deque: std::collections::VecDeque::from([0; 256]),
+        my_len: 0,
+        my_set: std::collections::BTreeSet::new(),
+        my_map: std::collections::HashMap::new(),
+        my_hash_set: std::collections::HashSet::new(),
+    };
+
+    let error = instance.serialize(Vec::new()).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::IntegerOverflow {
+            value: 256,
+            width: 1
+        })
+    ));
+
+    let instance = MyCollectionsStruct {
+        my_vec: vec![],
+        my_deque: std::collections::VecDeque::new(),
+        my_len: 2,
+        my_set: std::collections::BTreeSet::from([1]),
+        my_map: std::collections::HashMap::new(),
+        my_hash_set: std::collections::HashSet::new(),
+    };
+
+    let error = instance.serialize(Vec::new()).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::CountMismatch {
+            expected: 2,
+            actual: 1
+        })
+    ));
+}
+
+// Expressions see the fields as references, so the fields don't need to implement `Clone`.
+//
+#[derive(Serialize)]
+pub struct MyNonCloneHeader {
+    pub my_len: u8,
+}
+
+#[derive(Serialize)]
+pub struct MyNonCloneHeaderStruct {
+    pub my_header: MyNonCloneHeader,
+    #[serdine(count = "my_header.my_len")]
+    pub my_items: Vec<u8>,
+}
+
+#[test]
+fn test_serialize_expressions_with_non_clone_fields() {
+    let instance = MyNonCloneHeaderStruct {
+        my_header: MyNonCloneHeader { my_len: 2 },
+        my_items: vec![0x0A, 0x0B],
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    let expected_bytes: &[u8] = &[0x02, 0x0A, 0x0B];
+
+    assert_eq!(expected_bytes, serialized_instance);
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// SHARED REFERENCES
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Serialize)]
+pub struct MyLevel {
+    pub my_objects: Vec<std::rc::Rc<u16>>,
+    pub my_selection: Vec<std::rc::Rc<u16>>,
+}
+
+#[derive(Serialize)]
+pub struct MyWeakSelection {
+    pub my_objects: Vec<std::rc::Rc<u16>>,
+    pub my_selection: std::rc::Weak<u16>,
+}
+
+#[test]
+fn test_serialize_shared_references() {
+    let shared_object = std::rc::Rc::new(11);
+
+    let instance = MyLevel {
+        my_objects: vec![std::rc::Rc::new(10), shared_object.clone()],
+        my_selection: vec![shared_object],
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    serdine::shared::serialize_with_object_table(&instance, &mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x0A, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x0B, 0x00,
+        0x01, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+
+    // Without object table, shared values are duplicated.
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0x02, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x0B, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x0B, 0x00,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+}
+
+#[test]
+fn test_serialize_shared_references_weak() {
+    let shared_object = std::rc::Rc::new(10);
+
+    let instance = MyWeakSelection {
+        my_objects: vec![shared_object.clone()],
+        my_selection: std::rc::Rc::downgrade(&shared_object),
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    serdine::shared::serialize_with_object_table(&instance, &mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0x01, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x0A, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+
+    let error = instance.serialize(Vec::new()).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::ObjectTableRequired)
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// RUNTIME OPTIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Serialize)]
+pub struct MyRuntimeOptionsStruct {
+    pub my_u32: u32,
+    pub my_u24: serdine::U24,
+    pub my_enum: MyEnum,
+    pub my_string: String,
+    #[serdine(endian = "little")]
+    pub my_little_endian_u16: u16,
+}
+
+#[test]
+fn test_serialize_with_options() {
+    let instance = MyRuntimeOptionsStruct {
+        my_u32: 0xCAFEBABE,
+        my_u24: serdine::U24::new(0x010203).unwrap(),
+        my_enum: MyEnum::VarC,
+        my_string: "hi".to_string(),
+        my_little_endian_u16: 0x0201,
+    };
+
+    let options = serdine::Options::new().with_endianness(serdine::Endianness::Big);
+
+    let mut serialized_instance = Vec::new();
+
+    instance
+        .serialize_with_options(&mut serialized_instance, &options)
+        .unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0xCA, 0xFE, 0xBA, 0xBE,
+        0x01, 0x02, 0x03,
+        0xFF, 0xFE,
+        0x00, 0x00, 0x00, 0x02, 0x68, 0x69,
+        0x01, 0x02,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// BOOL LAYOUTS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Serialize)]
+pub struct MyBoolLayoutsStruct {
+    pub my_bool: bool,
+    #[serdine(bool_layout = "u16")]
+    pub my_u16_bool: bool,
+    #[serdine(bool_layout = "u32")]
+    pub my_u32_bool: bool,
+    #[serdine(bool_layout = "u8_ff", strict_bool)]
+    pub my_ff_bool: bool,
+}
+
+#[test]
+fn test_serialize_bool_layouts() {
+    let instance = MyBoolLayoutsStruct {
+        my_bool: true,
+        my_u16_bool: true,
+        my_u32_bool: false,
+        my_ff_bool: true,
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0x01,
+        0x01, 0x00,
+        0x00, 0x00, 0x00, 0x00,
+        0xFF,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// CONTEXT
+// ////////////////////////////////////////////////////////////////////////////////
+
+pub struct MyHeaderInfo {
+    pub version: u8,
+    pub palette_size: u8,
+}
+
+#[derive(Serialize)]
+#[serdine(ctx = "MyHeaderInfo")]
+pub struct MyPalette {
+    #[serdine(count = "ctx.palette_size")]
+    pub my_colors: Vec<u8>,
+    #[serialize = "serialize_palette_flags"]
+    pub my_flags: u16,
+}
+
+// The flags have been introduced in version 2.
+//
+fn serialize_palette_flags<W: std::io::Write>(
+    value: &u16,
+    w: W,
+    ctx: &MyHeaderInfo,
+) -> Result<(), std::io::Error> {
+    if ctx.version >= 2 {
+        value.serialize(w)
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+pub struct MyFile {
+    pub my_version: u8,
+    pub my_palette_size: u8,
+    #[serdine(ctx = "MyHeaderInfo { version: *my_version, palette_size: *my_palette_size }")]
+    pub my_palette: MyPalette,
+}
+
+#[test]
+fn test_serialize_with_ctx() {
+    let instance = MyFile {
+        my_version: 2,
+        my_palette_size: 3,
+        my_palette: MyPalette {
+            my_colors: vec![0x0A, 0x0B, 0x0C],
+            my_flags: 0xCAFE,
+        },
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0x02, 0x03,
+        0x0A, 0x0B, 0x0C,
+        0xFE, 0xCA,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+
+    // The count is checked against the context.
+    //
+    let instance = MyFile {
+        my_version: 1,
+        my_palette_size: 2,
+        my_palette: MyPalette {
+            my_colors: vec![0x0A],
+            my_flags: 0xCAFE,
+        },
+    };
+
+    let error = instance.serialize(&mut Vec::new()).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::CountMismatch {
+            expected: 2,
+            actual: 1
+        })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// CONDITIONAL FIELDS
+// ////////////////////////////////////////////////////////////////////////////////
+
+pub const MY_HAS_PALETTE: u8 = 0x01;
+
+#[derive(Serialize)]
+pub struct MyConditionalStruct {
+    pub my_flags: u8,
+    #[serdine(if = "my_flags & MY_HAS_PALETTE != 0")]
+    pub my_palette: Option<[u8; 3]>,
+    pub my_u8: u8,
+}
+
+#[test]
+fn test_serialize_conditional_fields() {
+    let instance = MyConditionalStruct {
+        my_flags: 0x01,
+        my_palette: Some([0x0A, 0x0B, 0x0C]),
+        my_u8: 0xCA,
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0x01,
+        0x0A, 0x0B, 0x0C,
+        0xCA,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+
+    let instance = MyConditionalStruct {
+        my_flags: 0x00,
+        my_palette: None,
+        my_u8: 0xCA,
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    assert_eq!(&[0x00, 0xCA], serialized_instance.as_slice());
+
+    // The presence of the value must match the condition.
+    //
+    let instance = MyConditionalStruct {
+        my_flags: 0x00,
+        my_palette: Some([0x0A, 0x0B, 0x0C]),
+        my_u8: 0xCA,
+    };
+
+    let error = instance.serialize(&mut Vec::new()).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::ConditionMismatch { condition: false })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// VERSIONED STRUCTS
+// ////////////////////////////////////////////////////////////////////////////////
+
+pub struct MyVersionInfo {
+    pub version: u8,
+}
+
+// The target version is passed via context.
+//
+#[derive(Serialize)]
+#[serdine(ctx = "MyVersionInfo", version = "ctx.version")]
+pub struct MyVersionedStruct {
+    pub my_u16: u16,
+    #[serdine(since = 2, until = 3)]
+    pub my_legacy_u8: u8,
+    #[serdine(since = 3)]
+    pub my_u32: u32,
+}
+
+#[test]
+fn test_serialize_versioned_struct() {
+    let instance = MyVersionedStruct {
+        my_u16: 0xCAFE,
+        my_legacy_u8: 0x80,
+        my_u32: 0xCAFEBABE,
+    };
+
+    let serialize_version = |version| {
+        let mut serialized_instance = Vec::new();
+        let ctx = MyVersionInfo { version };
+
+        serdine::SerializeWithCtx::serialize_with_ctx(&instance, &mut serialized_instance, &ctx)
+            .unwrap();
+
+        serialized_instance
+    };
+
+    assert_eq!(vec![0xFE, 0xCA], serialize_version(1));
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0xFE, 0xCA,
+        0x80,
+        0xBE, 0xBA, 0xFE, 0xCA,
+    ];
+
+    assert_eq!(expected_bytes, serialize_version(3));
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0xFE, 0xCA,
+        0xBE, 0xBA, 0xFE, 0xCA,
+    ];
+
+    assert_eq!(expected_bytes, serialize_version(4));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// MIGRATIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(serdine_derive::Deserialize, Serialize)]
+pub struct MyRecordV1 {
+    pub my_u16: u16,
+}
+
+#[derive(serdine_derive::Deserialize, Serialize)]
+pub struct MyRecordV2 {
+    pub my_u16: u16,
+    pub my_u8: u8,
+}
+
+#[derive(serdine_derive::Deserialize, Serialize)]
+pub struct MyRecordV3 {
+    pub my_u32: u32,
+    pub my_u8: u8,
+}
+
+impl From<MyRecordV1> for MyRecordV2 {
+    fn from(previous: MyRecordV1) -> Self {
+        Self {
+            my_u16: previous.my_u16,
+            my_u8: 0,
+        }
+    }
+}
+
+impl From<MyRecordV2> for MyRecordV3 {
+    fn from(previous: MyRecordV2) -> Self {
+        Self {
+            my_u32: previous.my_u16 as u32,
+            my_u8: previous.my_u8,
+        }
+    }
+}
+
+impl serdine::migrate::Version for MyRecordV1 {
+    const VERSION: u64 = 1;
+    type Previous = Self;
+}
+
+// Doesn't support downgrades.
+//
+impl serdine::migrate::Version for MyRecordV2 {
+    const VERSION: u64 = 2;
+    type Previous = MyRecordV1;
+}
+
+impl serdine::migrate::Version for MyRecordV3 {
+    const VERSION: u64 = 3;
+    type Previous = MyRecordV2;
+
+    fn downgrade(&self) -> Option<MyRecordV2> {
+        Some(MyRecordV2 {
+            my_u16: u16::try_from(self.my_u32).ok()?,
+            my_u8: self.my_u8,
+        })
+    }
+}
+
+#[test]
+fn test_serialize_migrations() {
+    let instance = MyRecordV3 {
+        my_u32: 0xCAFE,
+        my_u8: 0x80,
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    serdine::migrate::serialize_versioned::<u8, _, _>(&instance, &mut serialized_instance, 2)
+        .unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0x02,
+        0xFE, 0xCA,
+        0x80,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+
+    let error = serdine::migrate::serialize_version(&instance, &mut Vec::new(), 1).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::DowngradeUnsupported { version: 1 })
+    ));
+
+    let error = serdine::migrate::serialize_version(&instance, &mut Vec::new(), 4).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::UnknownVersion { version: 4 })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// HOOKS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Serialize)]
+#[serdine(pre_serialize = "Self::validate")]
+pub struct MyIndexedStruct {
+    pub my_values: [u8; 4],
+    pub my_max_index: u8,
+}
+
+impl MyIndexedStruct {
+    fn validate(&self) -> Result<Option<Self>, std::io::Error> {
+        if self.my_max_index as usize >= self.my_values.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Index out of range",
+            ));
+        }
+
+        Ok(None)
+    }
+}
+
+#[derive(Serialize)]
+#[serdine(pre_serialize = "Self::sort_values")]
+pub struct MySortedStruct {
+    pub my_label: u8,
+    #[serdine(len_prefix = "u8")]
+    pub my_values: Vec<u16>,
+}
+
+impl MySortedStruct {
+    fn sort_values(&self) -> Result<Option<Self>, std::io::Error> {
+        if self.my_values.windows(2).all(|pair| pair[0] <= pair[1]) {
+            return Ok(None);
+        }
+
+        let mut my_values = self.my_values.clone();
+        my_values.sort_unstable();
+
+        Ok(Some(Self {
+            my_label: self.my_label,
+            my_values,
+        }))
+    }
+}
+
+#[test]
+fn test_serialize_hooks() {
+    let instance = MyIndexedStruct {
+        my_values: [0x01, 0x02, 0x03, 0x04],
+        my_max_index: 3,
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    assert_eq!(
+        &[0x01, 0x02, 0x03, 0x04, 0x03],
+        serialized_instance.as_slice()
+    );
+
+    let instance = MyIndexedStruct {
+        my_values: [0x01, 0x02, 0x03, 0x04],
+        my_max_index: 4,
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    let error = instance.serialize(&mut serialized_instance).unwrap_err();
+
+    assert_eq!("Index out of range", error.to_string());
+
+    // The hook runs before any field is written.
+    //
+    assert!(serialized_instance.is_empty());
+}
+
+// The fields are serialized from the normalized value returned by the hook, if any.
+//
+#[test]
+fn test_serialize_hooks_normalization() {
+    let instance = MySortedStruct {
+        my_label: 0xAB,
+        my_values: vec![0x0302, 0x0100],
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0xAB,
+        0x02, 0x00, 0x01, 0x02, 0x03,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// CALCULATED FIELDS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Serialize)]
+pub struct MyCalculatedStruct {
+    #[serdine(calc = "self.my_items.len() as u16")]
+    pub my_count: u16,
+    #[serdine(
+        calc = "my_items.iter().map(|item| *item as u32).sum()",
+        encoding = "uleb128"
+    )]
+    pub my_sum: u32,
+    #[serdine(count = "my_count")]
+    pub my_items: Vec<u8>,
+}
+
+#[test]
+fn test_serialize_calculated_fields() {
+    // The stored values are ignored; expressions referring to calculated fields (here, `count`) use
+    // the calculated values.
+    //
+    let instance = MyCalculatedStruct {
+        my_count: 0,
+        my_sum: 0,
+        my_items: vec![0x40, 0x50],
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0x02, 0x00,
+        0x90, 0x01,
+        0x40, 0x50,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+
+    // The instance is not modified.
+    //
+    assert_eq!(0, instance.my_count);
+    assert_eq!(0, instance.my_sum);
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// CONVERSIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Serialize)]
+pub struct MyConvertedFieldsStruct {
+    #[serdine(as = "u16")]
+    pub my_len: usize,
+    #[serdine(as = "u16")]
+    pub my_u8: u8,
+}
+
+#[derive(Clone, Serialize)]
+#[serdine(into = "u32")]
+pub struct MyTimeout(pub std::time::Duration);
+
+impl TryFrom<MyTimeout> for u32 {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(timeout: MyTimeout) -> Result<Self, Self::Error> {
+        u32::try_from(timeout.0.as_millis())
+    }
+}
+
+#[test]
+fn test_serialize_conversions() {
+    let instance = MyConvertedFieldsStruct {
+        my_len: 0x1234,
+        my_u8: 0xFF,
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    assert_eq!(&[0x34, 0x12, 0xFF, 0x00], serialized_instance.as_slice());
+
+    // Narrowing conversions are checked.
+    //
+    let instance = MyConvertedFieldsStruct {
+        my_len: 0x10000,
+        my_u8: 0xFF,
+    };
+
+    let error = instance.serialize(&mut Vec::new()).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::ConversionFailed { .. })
+    ));
+
+    let instance = MyTimeout(std::time::Duration::from_secs(1));
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    assert_eq!(&[0xE8, 0x03, 0x00, 0x00], serialized_instance.as_slice());
+
+    let instance = MyTimeout(std::time::Duration::from_secs(u64::MAX));
+
+    let error = instance.serialize(&mut Vec::new()).unwrap_err();
+
+    assert!(matches!(
+        error.get_ref().unwrap().downcast_ref::<serdine::Error>(),
+        Some(serdine::Error::ConversionFailed { .. })
+    ));
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// TRANSPARENT TYPES
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Serialize)]
+#[serdine(transparent)]
+pub struct MyTileId(pub u16);
+
+#[derive(Serialize)]
+#[serdine(transparent)]
+pub struct MyTileName {
+    pub my_name: String,
+}
+
+#[derive(Serialize)]
+pub struct MyTile {
+    pub my_id: MyTileId,
+    pub my_name: MyTileName,
+}
+
+#[test]
+fn test_serialize_transparent_types() {
+    let instance = MyTile {
+        my_id: MyTileId(0xCAFE),
+        my_name: MyTileName {
+            my_name: "hi".to_string(),
+        },
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0xFE, 0xCA,
+        0x02, 0x00, 0x00, 0x00, 0x68, 0x69,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// REMOTE TYPES
+// ////////////////////////////////////////////////////////////////////////////////
+
+pub mod my_remote {
+    pub struct MyPoint {
+        pub x: i16,
+        pub y: i16,
+    }
+}
+
+#[derive(Serialize)]
+#[serdine(remote = "my_remote::MyPoint")]
+pub struct MyPointDef {
+    pub x: i16,
+    pub y: i16,
+}
+
+#[derive(Serialize)]
+#[serdine(remote = "std::net::Ipv4Addr")]
+pub struct MyIpv4AddrDef {
+    #[serdine(getter = "std::net::Ipv4Addr::octets")]
+    pub octets: [u8; 4],
+}
+
+#[derive(Serialize)]
+pub struct MyEndpoint {
+    #[serdine(with = "MyPointDef")]
+    pub my_point: my_remote::MyPoint,
+    #[serdine(with = "MyIpv4AddrDef")]
+    pub my_addr: std::net::Ipv4Addr,
+}
+
+#[test]
+fn test_serialize_remote_types() {
+    let instance = MyEndpoint {
+        my_point: my_remote::MyPoint { x: 1, y: -2 },
+        my_addr: std::net::Ipv4Addr::new(127, 0, 0, 1),
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    #[rustfmt::skip]
+    let expected_bytes: &[u8] = &[
+        0x01, 0x00, 0xFE, 0xFF,
+        0x7F, 0x00, 0x00, 0x01,
+    ];
+
+    assert_eq!(expected_bytes, serialized_instance);
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// FIXED SIZE
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Serialize, FixedSize)]
+#[repr(u16)]
+pub enum MyRecordKind {
+    #[allow(dead_code)]
+    Header = 1,
+    Data = 2,
+}
+
+#[derive(Serialize, FixedSize)]
+#[serdine(transparent)]
+pub struct MyRecordId(pub u32);
+
+#[derive(Serialize, FixedSize)]
+pub struct MyRecord {
+    pub my_id: MyRecordId,
+    pub my_kind: MyRecordKind,
+    #[serdine(width = 3)]
+    pub my_offset: u32,
+    #[serdine(bool_layout = "u32")]
+    pub my_flag: bool,
+    #[serdine(none_value = 0xFFFF)]
+    pub my_parent: Option<u16>,
+    pub my_tag: [u8; 4],
+    pub my_position: (i16, i16),
+    pub my_len: usize,
+}
+
+#[test]
+fn test_fixed_size() {
+    let instance = MyRecord {
+        my_id: MyRecordId(1),
+        my_kind: MyRecordKind::Data,
+        my_offset: 0x00CAFE,
+        my_flag: true,
+        my_parent: None,
+        my_tag: *b"DATA",
+        my_position: (-1, 1),
+        my_len: 16,
+    };
+
+    let mut serialized_instance = Vec::new();
+
+    instance.serialize(&mut serialized_instance).unwrap();
+
+    assert_eq!(31, <MyRecord as serdine::FixedSize>::SIZE);
+    assert_eq!(
+        serialized_instance.len(),
+        <MyRecord as serdine::FixedSize>::SIZE
+    );
+    assert_eq!(62, <[MyRecord; 2] as serdine::FixedSize>::SIZE);
+}
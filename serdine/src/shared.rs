@@ -0,0 +1,321 @@
+//! Shared references (`Rc`/`Arc`, and their `Weak` counterparts).
+//!
+//! By default, shared pointers are stored as the value they point to, so shared values are
+//! duplicated, and their identity is lost.
+//!
+//! When de/serializing via `serialize_with_object_table()`/`deserialize_with_object_table()`, each
+//! pointed value is assigned an id (a `u32`), in order of occurrence; the first occurrence of a
+//! pointer is stored as the id followed by the value, and the next ones as the id only. On
+//! deserialization, the sharing is reconstructed.
+//!
+//! Weak pointers are supported only in object table mode; dangling ones are stored as `NULL_ID`.
+//!
+//! Weak pointers can refer to a value that is still being deserialized (e.g. a back-reference from
+//! a child to its parent), while strong pointers can't (it would be a reference cycle), and are
+//! rejected with `Error::UnresolvedObjectReference`.
+//!
+//! Values in construction are built via `Rc::new_cyclic()`/`Arc::new_cyclic()`, which can't fail;
+//! the construction is performed on a `MaybeUninit<T>`, which is left uninitialized in case of
+//! error, and converted to `T` on success.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::mem::MaybeUninit;
+use std::rc::{self, Rc};
+use std::sync::{self, Arc};
+use std::thread::LocalKey;
+
+use crate::{limits, Deserialize, Error, Serialize};
+
+/// Id of dangling weak pointers.
+///
+pub const NULL_ID: u32 = u32::MAX;
+
+// ////////////////////////////////////////////////////////////////////////////////
+// OBJECT TABLES
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Default)]
+struct SerializationTable {
+    // Keyed by the address of the pointed values.
+    ids: HashMap<*const (), u32>,
+}
+
+enum TableEntry {
+    // Weak pointer to a value in construction.
+    Pending(Box<dyn Any>),
+    // Strong pointer to a constructed value; keeps it alive until the end of the deserialization.
+    Resolved(Box<dyn Any>),
+}
+
+#[derive(Default)]
+struct DeserializationTable {
+    entries: Vec<TableEntry>,
+}
+
+thread_local! {
+    static SERIALIZATION_TABLE: RefCell<Option<SerializationTable>> = const { RefCell::new(None) };
+    static DESERIALIZATION_TABLE: RefCell<Option<DeserializationTable>> = const { RefCell::new(None) };
+}
+
+/// Serializes a value, storing each shared value once.
+///
+pub fn serialize_with_object_table<T, W>(value: &T, w: W) -> Result<(), std::io::Error>
+where
+    T: Serialize + ?Sized,
+    W: Write,
+{
+    let _guard = TableGuard::install(&SERIALIZATION_TABLE);
+
+    value.serialize(w)
+}
+
+/// Deserializes a value serialized via `serialize_with_object_table()`.
+///
+pub fn deserialize_with_object_table<T, R>(r: R) -> Result<T, std::io::Error>
+where
+    T: Deserialize,
+    R: Read,
+{
+    let _guard = TableGuard::install(&DESERIALIZATION_TABLE);
+
+    T::deserialize(r)
+}
+
+// Installs a new table, and restores the previous one (if any) on drop, also in case of panic.
+//
+struct TableGuard<T: 'static> {
+    key: &'static LocalKey<RefCell<Option<T>>>,
+    previous_table: Option<T>,
+}
+
+impl<T: Default> TableGuard<T> {
+    fn install(key: &'static LocalKey<RefCell<Option<T>>>) -> Self {
+        let previous_table = key.with(|table| table.replace(Some(T::default())));
+
+        Self {
+            key,
+            previous_table,
+        }
+    }
+}
+
+impl<T> Drop for TableGuard<T> {
+    fn drop(&mut self) {
+        let previous_table = self.previous_table.take();
+        self.key.with(|table| table.replace(previous_table));
+    }
+}
+
+fn in_serialization_table_mode() -> bool {
+    SERIALIZATION_TABLE.with(|table| table.borrow().is_some())
+}
+
+fn in_deserialization_table_mode() -> bool {
+    DESERIALIZATION_TABLE.with(|table| table.borrow().is_some())
+}
+
+// Returns the id, and whether the pointer has been just registered.
+//
+fn register_pointer(address: *const ()) -> (u32, bool) {
+    SERIALIZATION_TABLE.with(|table| {
+        let mut table = table.borrow_mut();
+        let table = table.as_mut().unwrap();
+
+        let next_id = table.ids.len() as u32;
+
+        match table.ids.get(&address) {
+            Some(id) => (*id, false),
+            None => {
+                table.ids.insert(address, next_id);
+                (next_id, true)
+            }
+        }
+    })
+}
+
+fn next_entry_id() -> u32 {
+    DESERIALIZATION_TABLE.with(|table| table.borrow().as_ref().unwrap().entries.len() as u32)
+}
+
+fn push_entry(entry: TableEntry) {
+    DESERIALIZATION_TABLE.with(|table| table.borrow_mut().as_mut().unwrap().entries.push(entry));
+}
+
+fn set_entry(id: u32, entry: TableEntry) {
+    DESERIALIZATION_TABLE.with(|table| {
+        table.borrow_mut().as_mut().unwrap().entries[id as usize] = entry;
+    });
+}
+
+// The callback receives the entry, if present.
+//
+fn with_entry<F, U>(id: u32, f: F) -> U
+where
+    F: FnOnce(Option<&TableEntry>) -> U,
+{
+    DESERIALIZATION_TABLE.with(|table| f(table.borrow().as_ref().unwrap().entries.get(id as usize)))
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// POINTER IMPLEMENTATIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+macro_rules! impl_for_shared_pointer {
+    ( $pointer:ident, $weak:ty, $try_new_cyclic:ident, $deserialize_shared:ident ) => {
+        impl<T: Serialize + ?Sized> Serialize for $pointer<T> {
+            fn serialize<W: Write>(&self, mut w: W) -> Result<(), std::io::Error> {
+                // See the `Box` implementation about the type erasure.
+                //
+                let mut w = &mut w as &mut dyn Write;
+
+                if !in_serialization_table_mode() {
+                    return (**self).serialize(w);
+                }
+
+                let (id, is_new) = register_pointer($pointer::as_ptr(self) as *const ());
+
+                id.serialize(&mut w)?;
+
+                if is_new {
+                    (**self).serialize(w)?;
+                }
+
+                Ok(())
+            }
+        }
+
+        impl<T: Deserialize + 'static> Deserialize for $pointer<T> {
+            fn deserialize<R: Read>(mut r: R) -> Result<Self, std::io::Error> {
+                let _nesting_guard = limits::enter_nesting()?;
+
+                if !in_deserialization_table_mode() {
+                    return T::deserialize(&mut r as &mut dyn Read).map($pointer::new);
+                }
+
+                let id = u32::deserialize(&mut r)?;
+
+                if id == next_entry_id() {
+                    return $deserialize_shared(r, id);
+                }
+
+                let existing = with_entry(id, |entry| match entry {
+                    Some(TableEntry::Resolved(pointer)) => pointer
+                        .downcast_ref::<$pointer<T>>()
+                        .cloned()
+                        .ok_or(Error::InvalidObjectReference { id }),
+                    Some(TableEntry::Pending(_)) => Err(Error::UnresolvedObjectReference { id }),
+                    None => Err(Error::InvalidObjectReference { id }),
+                });
+
+                existing.map_err(std::io::Error::from)
+            }
+        }
+
+        impl<T: Serialize> Serialize for $weak {
+            fn serialize<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+                if !in_serialization_table_mode() {
+                    return Err(Error::ObjectTableRequired.into());
+                }
+
+                match self.upgrade() {
+                    Some(pointer) => pointer.serialize(w),
+                    None => NULL_ID.serialize(w),
+                }
+            }
+        }
+
+        impl<T: Deserialize + 'static> Deserialize for $weak {
+            fn deserialize<R: Read>(mut r: R) -> Result<Self, std::io::Error> {
+                if !in_deserialization_table_mode() {
+                    return Err(Error::ObjectTableRequired.into());
+                }
+
+                let _nesting_guard = limits::enter_nesting()?;
+
+                let id = u32::deserialize(&mut r)?;
+
+                if id == NULL_ID {
+                    return Ok(<$weak>::new());
+                }
+
+                if id == next_entry_id() {
+                    // The table keeps the value alive.
+                    //
+                    let pointer = $deserialize_shared::<T, R>(r, id)?;
+                    return Ok($pointer::downgrade(&pointer));
+                }
+
+                let existing = with_entry(id, |entry| match entry {
+                    Some(TableEntry::Resolved(pointer)) => pointer
+                        .downcast_ref::<$pointer<T>>()
+                        .map($pointer::downgrade)
+                        .ok_or(Error::InvalidObjectReference { id }),
+                    Some(TableEntry::Pending(weak)) => weak
+                        .downcast_ref::<$weak>()
+                        .cloned()
+                        .ok_or(Error::InvalidObjectReference { id }),
+                    None => Err(Error::InvalidObjectReference { id }),
+                });
+
+                existing.map_err(std::io::Error::from)
+            }
+        }
+
+        fn $deserialize_shared<T, R>(mut r: R, id: u32) -> Result<$pointer<T>, std::io::Error>
+        where
+            T: Deserialize + 'static,
+            R: Read,
+        {
+            let pointer = $try_new_cyclic(|weak: &$weak| {
+                push_entry(TableEntry::Pending(Box::new(weak.clone())));
+                T::deserialize(&mut r as &mut dyn Read)
+            })?;
+
+            set_entry(id, TableEntry::Resolved(Box::new(pointer.clone())));
+
+            Ok(pointer)
+        }
+
+        fn $try_new_cyclic<T, F>(f: F) -> Result<$pointer<T>, std::io::Error>
+        where
+            F: FnOnce(&$weak) -> Result<T, std::io::Error>,
+        {
+            let mut error = None;
+
+            let pointer = $pointer::<MaybeUninit<T>>::new_cyclic(|uninit_weak| {
+                // SAFETY: `MaybeUninit<T>` has the same layout as `T`, as required by the pointer
+                // conversion. The weak pointer can't be upgraded while the value is in construction,
+                // and, in case of error, the value is dropped without being initialized, so that
+                // the uninitialized value is never accessed as `T`.
+                //
+                let weak = unsafe { <$weak>::from_raw(uninit_weak.clone().into_raw().cast::<T>()) };
+
+                match f(&weak) {
+                    Ok(value) => MaybeUninit::new(value),
+                    Err(construction_error) => {
+                        error = Some(construction_error);
+                        MaybeUninit::uninit()
+                    }
+                }
+            });
+
+            match error {
+                Some(error) => Err(error),
+                // SAFETY: The value has been initialized, and the layouts are the same (see above).
+                //
+                None => Ok(unsafe { $pointer::from_raw($pointer::into_raw(pointer).cast::<T>()) }),
+            }
+        }
+    };
+}
+
+impl_for_shared_pointer!(Rc, rc::Weak<T>, try_new_cyclic_rc, deserialize_shared_rc);
+impl_for_shared_pointer!(
+    Arc,
+    sync::Weak<T>,
+    try_new_cyclic_arc,
+    deserialize_shared_arc
+);
@@ -0,0 +1,300 @@
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+use std::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+    NonZeroU32, NonZeroU64, NonZeroU8, Wrapping,
+};
+
+use crate::{limits, Deserialize, Error, FixedSize, Serialize};
+
+// ////////////////////////////////////////////////////////////////////////////////
+// PLATFORM-DEPENDENT INTEGERS
+// ////////////////////////////////////////////////////////////////////////////////
+
+// usize/isize are always stored as 64 bits, so that the format doesn't depend on the platform.
+
+impl Deserialize for usize {
+    fn deserialize<R: Read>(r: R) -> Result<Self, std::io::Error> {
+        let value = u64::deserialize(r)?;
+
+        usize::try_from(value).map_err(|_| {
+            Error::IntegerOverflow {
+                value: value as i128,
+                width: u64::SIZE,
+            }
+            .into()
+        })
+    }
+}
+
+impl Serialize for usize {
+    fn serialize<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+        (*self as u64).serialize(w)
+    }
+}
+
+impl FixedSize for usize {
+    const SIZE: usize = u64::SIZE;
+}
+
+impl Deserialize for isize {
+    fn deserialize<R: Read>(r: R) -> Result<Self, std::io::Error> {
+        let value = i64::deserialize(r)?;
+
+        isize::try_from(value).map_err(|_| {
+            Error::IntegerOverflow {
+                value: value as i128,
+                width: i64::SIZE,
+            }
+            .into()
+        })
+    }
+}
+
+impl Serialize for isize {
+    fn serialize<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+        (*self as i64).serialize(w)
+    }
+}
+
+impl FixedSize for isize {
+    const SIZE: usize = i64::SIZE;
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// CHAR
+// ////////////////////////////////////////////////////////////////////////////////
+
+// Stored as the 32-bit code point.
+
+impl Deserialize for char {
+    fn deserialize<R: Read>(r: R) -> Result<Self, std::io::Error> {
+        let value = u32::deserialize(r)?;
+
+        char::from_u32(value).ok_or_else(|| Error::InvalidChar { value }.into())
+    }
+}
+
+impl Serialize for char {
+    fn serialize<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+        (*self as u32).serialize(w)
+    }
+}
+
+impl FixedSize for char {
+    const SIZE: usize = u32::SIZE;
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// NONZERO
+// ////////////////////////////////////////////////////////////////////////////////
+
+macro_rules! impl_for_non_zero {
+    ( $( $type:ty => $inner:ty ),+ ) => {
+        $(
+            impl Deserialize for $type {
+                fn deserialize<R: Read>(r: R) -> Result<Self, std::io::Error> {
+                    let value = <$inner>::deserialize(r)?;
+
+                    <$type>::new(value).ok_or_else(|| {
+                        Error::UnexpectedZero {
+                            type_name: std::any::type_name::<$type>(),
+                        }
+                        .into()
+                    })
+                }
+            }
+
+            impl Serialize for $type {
+                fn serialize<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+                    self.get().serialize(w)
+                }
+            }
+
+            impl FixedSize for $type {
+                const SIZE: usize = <$inner>::SIZE;
+            }
+        )+
+    };
+}
+
+impl_for_non_zero!(
+    NonZeroI8 => i8, NonZeroI16 => i16, NonZeroI32 => i32, NonZeroI64 => i64, NonZeroI128 => i128,
+    NonZeroU8 => u8, NonZeroU16 => u16, NonZeroU32 => u32, NonZeroU64 => u64, NonZeroU128 => u128
+);
+
+// ////////////////////////////////////////////////////////////////////////////////
+// TUPLES
+// ////////////////////////////////////////////////////////////////////////////////
+
+macro_rules! impl_for_tuple {
+    ( $( ( $( $name:ident $index:tt ),+ ) )+ ) => {
+        $(
+            impl<$( $name: Deserialize ),+> Deserialize for ($( $name, )+) {
+                fn deserialize<R: Read>(mut r: R) -> Result<Self, std::io::Error> {
+                    // Tuple expressions are evaluated left to right.
+                    //
+                    Ok(($( $name::deserialize(&mut r)?, )+))
+                }
+            }
+
+            impl<$( $name: Serialize ),+> Serialize for ($( $name, )+) {
+                fn serialize<W: Write>(&self, mut w: W) -> Result<(), std::io::Error> {
+                    $( self.$index.serialize(&mut w)?; )+
+                    Ok(())
+                }
+            }
+
+            impl<$( $name: FixedSize ),+> FixedSize for ($( $name, )+) {
+                const SIZE: usize = 0 $( + $name::SIZE )+;
+            }
+        )+
+    };
+}
+
+impl_for_tuple!(
+    (T0 0)
+    (T0 0, T1 1)
+    (T0 0, T1 1, T2 2)
+    (T0 0, T1 1, T2 2, T3 3)
+    (T0 0, T1 1, T2 2, T3 3, T4 4)
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5)
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6)
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7)
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8)
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9)
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10)
+    (T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10, T11 11)
+);
+
+// Zero-sized types are not stored.
+
+impl Deserialize for () {
+    fn deserialize<R: Read>(_r: R) -> Result<Self, std::io::Error> {
+        Ok(())
+    }
+}
+
+impl Serialize for () {
+    fn serialize<W: Write>(&self, _w: W) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+}
+
+impl FixedSize for () {
+    const SIZE: usize = 0;
+}
+
+impl<T> Deserialize for PhantomData<T> {
+    fn deserialize<R: Read>(_r: R) -> Result<Self, std::io::Error> {
+        Ok(PhantomData)
+    }
+}
+
+impl<T> Serialize for PhantomData<T> {
+    fn serialize<W: Write>(&self, _w: W) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+}
+
+impl<T> FixedSize for PhantomData<T> {
+    const SIZE: usize = 0;
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// WRAPPERS
+// ////////////////////////////////////////////////////////////////////////////////
+
+// Wrappers are stored as the wrapped value.
+
+impl<T: Deserialize> Deserialize for Wrapping<T> {
+    fn deserialize<R: Read>(r: R) -> Result<Self, std::io::Error> {
+        T::deserialize(r).map(Wrapping)
+    }
+}
+
+impl<T: Serialize> Serialize for Wrapping<T> {
+    fn serialize<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+        self.0.serialize(w)
+    }
+}
+
+impl<T: FixedSize> FixedSize for Wrapping<T> {
+    const SIZE: usize = T::SIZE;
+}
+
+impl<T: Deserialize> Deserialize for Reverse<T> {
+    fn deserialize<R: Read>(r: R) -> Result<Self, std::io::Error> {
+        T::deserialize(r).map(Reverse)
+    }
+}
+
+impl<T: Serialize> Serialize for Reverse<T> {
+    fn serialize<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+        self.0.serialize(w)
+    }
+}
+
+impl<T: FixedSize> FixedSize for Reverse<T> {
+    const SIZE: usize = T::SIZE;
+}
+
+// Pointers are the indirection of recursive types; the reader/writer is type-erased, so that the
+// nested calls don't instantiate the trait methods with ever-growing `&mut &mut ...` types.
+
+impl<T: Deserialize> Deserialize for Box<T> {
+    fn deserialize<R: Read>(mut r: R) -> Result<Self, std::io::Error> {
+        let _nesting_guard = limits::enter_nesting()?;
+
+        T::deserialize(&mut r as &mut dyn Read).map(Box::new)
+    }
+}
+
+impl<T: Serialize + ?Sized> Serialize for Box<T> {
+    fn serialize<W: Write>(&self, mut w: W) -> Result<(), std::io::Error> {
+        (**self).serialize(&mut w as &mut dyn Write)
+    }
+}
+
+impl<T: FixedSize> FixedSize for Box<T> {
+    const SIZE: usize = T::SIZE;
+}
+
+impl<T: Deserialize> Deserialize for Cell<T> {
+    fn deserialize<R: Read>(r: R) -> Result<Self, std::io::Error> {
+        T::deserialize(r).map(Cell::new)
+    }
+}
+
+impl<T: Serialize + Copy> Serialize for Cell<T> {
+    fn serialize<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+        self.get().serialize(w)
+    }
+}
+
+impl<T: FixedSize> FixedSize for Cell<T> {
+    const SIZE: usize = T::SIZE;
+}
+
+impl<T: Deserialize> Deserialize for RefCell<T> {
+    fn deserialize<R: Read>(r: R) -> Result<Self, std::io::Error> {
+        T::deserialize(r).map(RefCell::new)
+    }
+}
+
+impl<T: Serialize> Serialize for RefCell<T> {
+    fn serialize<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+        let value = self
+            .try_borrow()
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::Other, error.to_string()))?;
+
+        value.serialize(w)
+    }
+}
+
+impl<T: FixedSize> FixedSize for RefCell<T> {
+    const SIZE: usize = T::SIZE;
+}
@@ -0,0 +1,255 @@
+//! Variable-length integer encodings.
+//!
+//! The encodings are available both as wrapper types (e.g. `Uleb128<u32>`), and as functions,
+//! which are what the `encoding` field attribute is based on:
+//!
+//! - `uleb128`: unsigned LEB128 (WebAssembly, DWARF...)
+//! - `sleb128`: signed LEB128
+//! - `zigzag`: zigzag mapping of a signed value, encoded as unsigned LEB128 (protobuf `sint*`)
+//! - `vlq`: big endian groups of 7 bits (MIDI delta times, git pack headers)
+//!
+//! Encodings with redundant groups are rejected as `Error::OverlongEncoding`; values that don't fit
+//! the target type are rejected as `Error::VarintOutOfRange`.
+
+use std::io::{Read, Write};
+
+use crate::odd_width::WidthInteger;
+use crate::{Deserialize, Error, Serialize};
+
+const GROUP_BITS: u32 = 7;
+const GROUP_MASK: u8 = 0x7F;
+const CONTINUATION_BIT: u8 = 0x80;
+const SLEB128_SIGN_BIT: u8 = 0x40;
+
+// ////////////////////////////////////////////////////////////////////////////////
+// WRAPPER TYPES
+// ////////////////////////////////////////////////////////////////////////////////
+
+macro_rules! varint_wrapper {
+    ( $( $(#[$doc:meta])* $name:ident => $deserialize_fn:ident, $serialize_fn:ident ),+ $(,)? ) => {
+        $(
+            $(#[$doc])*
+            #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name<T>(pub T);
+
+            impl<T: WidthInteger> Deserialize for $name<T> {
+                fn deserialize<R: Read>(r: R) -> Result<Self, std::io::Error> {
+                    $deserialize_fn(r).map(Self)
+                }
+            }
+
+            impl<T: WidthInteger> Serialize for $name<T> {
+                fn serialize<W: Write>(&self, w: W) -> Result<(), std::io::Error> {
+                    $serialize_fn(&self.0, w)
+                }
+            }
+        )+
+    };
+}
+
+varint_wrapper!(
+    /// Unsigned LEB128-encoded integer.
+    Uleb128 => deserialize_uleb128, serialize_uleb128,
+    /// Signed LEB128-encoded integer.
+    Sleb128 => deserialize_sleb128, serialize_sleb128,
+    /// Zigzag-mapped, unsigned LEB128-encoded integer.
+    ZigZag => deserialize_zigzag, serialize_zigzag,
+    /// MIDI-style VLQ-encoded integer.
+    Vlq => deserialize_vlq, serialize_vlq,
+);
+
+// ////////////////////////////////////////////////////////////////////////////////
+// LEB128
+// ////////////////////////////////////////////////////////////////////////////////
+
+pub fn deserialize_uleb128<T: WidthInteger, R: Read>(r: R) -> Result<T, std::io::Error> {
+    let (value, _) = read_leb128::<T, R>(r, false)?;
+
+    into_target(value as i128)
+}
+
+pub fn serialize_uleb128<T: WidthInteger, W: Write>(value: &T, w: W) -> Result<(), std::io::Error> {
+    let value = value.to_i128();
+
+    if value < 0 {
+        return Err(Error::VarintOutOfRange.into());
+    }
+
+    write_uleb128(value as u128, w)
+}
+
+pub fn deserialize_sleb128<T: WidthInteger, R: Read>(r: R) -> Result<T, std::io::Error> {
+    let (value, bits) = read_leb128::<T, R>(r, true)?;
+
+    // The sign bit is the top bit of the last group.
+    //
+    let value = if value & (1 << (bits - 1)) != 0 {
+        value as i128 - (1_i128 << bits)
+    } else {
+        value as i128
+    };
+
+    into_target(value)
+}
+
+pub fn serialize_sleb128<T: WidthInteger, W: Write>(
+    value: &T,
+    mut w: W,
+) -> Result<(), std::io::Error> {
+    let mut value = value.to_i128();
+
+    loop {
+        let group = (value as u8) & GROUP_MASK;
+        value >>= GROUP_BITS;
+
+        let sign_bit_set = group & SLEB128_SIGN_BIT != 0;
+
+        if (value == 0 && !sign_bit_set) || (value == -1 && sign_bit_set) {
+            return w.write_all(&[group]);
+        }
+
+        w.write_all(&[group | CONTINUATION_BIT])?;
+    }
+}
+
+pub fn deserialize_zigzag<T: WidthInteger, R: Read>(r: R) -> Result<T, std::io::Error> {
+    let (value, _) = read_leb128::<T, R>(r, false)?;
+
+    let value = (value >> 1) as i128 ^ -((value & 1) as i128);
+
+    into_target(value)
+}
+
+pub fn serialize_zigzag<T: WidthInteger, W: Write>(value: &T, w: W) -> Result<(), std::io::Error> {
+    let value = value.to_i128();
+
+    let value = if value >= 0 {
+        (value as u128) << 1
+    } else {
+        ((-(value + 1)) as u128) << 1 | 1
+    };
+
+    write_uleb128(value, w)
+}
+
+// Returns the raw value, and its number of bits (always a multiple of the group size).
+//
+fn read_leb128<T: WidthInteger, R: Read>(
+    mut r: R,
+    signed: bool,
+) -> Result<(u128, u32), std::io::Error> {
+    let mut value: u128 = 0;
+    let mut shift = 0;
+
+    for group_index in 0..max_groups::<T>() {
+        let byte = read_byte(&mut r)?;
+
+        value |= ((byte & GROUP_MASK) as u128) << shift;
+        shift += GROUP_BITS;
+
+        if byte & CONTINUATION_BIT == 0 {
+            // A final group that adds no information (all zeros, or all ones for sign extended
+            // negative numbers) is redundant.
+            //
+            if group_index > 0 && is_redundant_final_group(byte, value, shift, signed) {
+                return Err(Error::OverlongEncoding.into());
+            }
+
+            return Ok((value, shift));
+        }
+    }
+
+    Err(Error::VarintOutOfRange.into())
+}
+
+fn is_redundant_final_group(byte: u8, value: u128, shift: u32, signed: bool) -> bool {
+    let previous_sign_bit_set = value & (1 << (shift - GROUP_BITS - 1)) != 0;
+
+    match byte {
+        0 => !signed || !previous_sign_bit_set,
+        GROUP_MASK => signed && previous_sign_bit_set,
+        _ => false,
+    }
+}
+
+fn write_uleb128<W: Write>(mut value: u128, mut w: W) -> Result<(), std::io::Error> {
+    loop {
+        let group = (value as u8) & GROUP_MASK;
+        value >>= GROUP_BITS;
+
+        if value == 0 {
+            return w.write_all(&[group]);
+        }
+
+        w.write_all(&[group | CONTINUATION_BIT])?;
+    }
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// VLQ
+// ////////////////////////////////////////////////////////////////////////////////
+
+pub fn deserialize_vlq<T: WidthInteger, R: Read>(mut r: R) -> Result<T, std::io::Error> {
+    let mut value: u128 = 0;
+
+    for group_index in 0..max_groups::<T>() {
+        let byte = read_byte(&mut r)?;
+
+        // Leading groups with no value bits are redundant.
+        //
+        if group_index == 0 && byte == CONTINUATION_BIT {
+            return Err(Error::OverlongEncoding.into());
+        }
+
+        value = value << GROUP_BITS | (byte & GROUP_MASK) as u128;
+
+        if byte & CONTINUATION_BIT == 0 {
+            return into_target(value as i128);
+        }
+    }
+
+    Err(Error::VarintOutOfRange.into())
+}
+
+pub fn serialize_vlq<T: WidthInteger, W: Write>(value: &T, mut w: W) -> Result<(), std::io::Error> {
+    let value = value.to_i128();
+
+    if value < 0 {
+        return Err(Error::VarintOutOfRange.into());
+    }
+
+    let mut value = value as u128;
+
+    // Groups are collected in reverse order, with the last one not flagged.
+    //
+    let mut buffer = vec![(value as u8) & GROUP_MASK];
+    value >>= GROUP_BITS;
+
+    while value != 0 {
+        buffer.push((value as u8) & GROUP_MASK | CONTINUATION_BIT);
+        value >>= GROUP_BITS;
+    }
+
+    buffer.reverse();
+
+    w.write_all(&buffer)
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// HELPERS
+// ////////////////////////////////////////////////////////////////////////////////
+
+fn max_groups<T: WidthInteger>() -> u32 {
+    let bits = T::WIDTH as u32 * 8;
+    (bits + GROUP_BITS - 1) / GROUP_BITS
+}
+
+fn into_target<T: WidthInteger>(value: i128) -> Result<T, std::io::Error> {
+    T::from_i128(value).ok_or_else(|| Error::VarintOutOfRange.into())
+}
+
+fn read_byte<R: Read>(mut r: R) -> Result<u8, std::io::Error> {
+    let mut buffer = [0; 1];
+    r.read_exact(&mut buffer)?;
+    Ok(buffer[0])
+}
@@ -0,0 +1,43 @@
+use proc_macro2::{Ident, Span};
+use quote::{quote, ToTokens};
+use syn::{Expr, LitStr};
+
+use crate::bitfield::field_name_tokens;
+
+type TokenStream2 = proc_macro2::TokenStream;
+
+// Field assertions report the field name and value (which must implement `Debug`); container
+// assertions report neither. When no message is specified, the assertion source is used.
+//
+pub fn assertion_tokens(
+    assertion: &Expr,
+    message: &Option<LitStr>,
+    field: Option<&Ident>,
+) -> TokenStream2 {
+    let message = match message {
+        Some(message) => message.clone(),
+        None => LitStr::new(&assertion.to_token_stream().to_string(), Span::call_site()),
+    };
+
+    let (field_name, value) = match field {
+        Some(field) => {
+            let field_name = field_name_tokens(field);
+            (
+                quote! { Some(#field_name) },
+                quote! { Some(std::format!("{:?}", #field)) },
+            )
+        }
+        None => (quote! { None }, quote! { None }),
+    };
+
+    quote! {
+        if !(#assertion) {
+            return Err(serdine::Error::AssertionFailed {
+                field: #field_name,
+                value: #value,
+                message: std::string::String::from(#message),
+            }
+            .into());
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::bail::bail;
+use crate::bitfield::{field_name_tokens, validate_bit_level_fields};
+use crate::collection::collect_named_fields_data;
+use crate::fields_data::NamedFieldData;
+use crate::target::Target::ForDeserialization;
+
+use proc_macro2::Ident;
+use quote::quote;
+use syn::{self, parse2, Data, DataStruct, DeriveInput, Fields};
+
+type TokenStream2 = proc_macro2::TokenStream;
+
+pub(crate) fn impl_bit_deserialize(input: impl Into<TokenStream2>) -> syn::Result<TokenStream2> {
+    let ast: DeriveInput = parse2(input.into())?;
+    let type_name = &ast.ident;
+
+    let deserialize_impl = match &ast.data {
+        Data::Struct(DataStruct { fields, .. }) => match fields {
+            Fields::Named(fields) => {
+                let named_fields_data = collect_named_fields_data(fields, ForDeserialization)?;
+                validate_bit_level_fields(&named_fields_data)?;
+                impl_trait_with_named_fields(type_name, named_fields_data)?
+            }
+            Fields::Unnamed(_) => bail!("Unnamed fields not supported!"),
+            Fields::Unit => bail!("Unit fields not supported!"),
+        },
+        Data::Enum(_) => bail!("Enums not supported! Use `bits` on the enum fields instead."),
+        Data::Union(_) => bail!("Unions not supported!"),
+    };
+
+    Ok(quote!(
+        #deserialize_impl
+    ))
+}
+
+fn impl_trait_with_named_fields(
+    type_name: &Ident,
+    fields_data: Vec<NamedFieldData>,
+) -> syn::Result<TokenStream2> {
+    let fields_deserialization = fields_data.iter().map(
+        |NamedFieldData {
+             field, bits, align, ..
+         }| {
+            let alignment = if *align {
+                quote! { r.align(); }
+            } else {
+                quote! {}
+            };
+
+            let deserialization = if let Some(bits) = bits {
+                let field_name = field_name_tokens(field);
+                quote! { serdine::bits::read_field(r, #bits, #field_name)? }
+            } else {
+                quote! { serdine::bits::BitDeserialize::bit_deserialize(r)? }
+            };
+
+            quote! {
+                #alignment
+                let #field = #deserialization;
+            }
+        },
+    );
+
+    let self_fields = fields_data
+        .iter()
+        .map(|NamedFieldData { field, .. }| quote! { #field, });
+
+    Ok(quote!(
+        impl serdine::bits::BitDeserialize for #type_name {
+            fn bit_deserialize<R: std::io::Read>(
+                r: &mut serdine::bits::BitReader<R>,
+            ) -> Result<Self, std::io::Error> {
+                #(#fields_deserialization)*
+
+                let result = Self {
+                    #(#self_fields)*
+                };
+
+                Ok(result)
+            }
+        }
+    ))
+}
@@ -0,0 +1,120 @@
+use proc_macro2::Ident;
+use quote::quote;
+use syn::LitStr;
+
+use crate::bail::bail;
+use crate::collection::{ALIGN_ATTR, BITS_ATTR};
+use crate::fields_data::{ContainerData, NamedFieldData};
+
+type TokenStream2 = proc_macro2::TokenStream;
+
+pub struct BitfieldLayout {
+    pub offset: u32,
+    pub width: u32,
+}
+
+// Returns the layout of each field, in the same order as the fields.
+//
+pub fn compute_bitfield_layouts(
+    storage_type: &Ident,
+    container_data: &ContainerData,
+    fields_data: &[NamedFieldData],
+) -> syn::Result<Vec<BitfieldLayout>> {
+    // The storage type is validated on collection, so the parsing can't fail.
+    //
+    let storage_bits = storage_type.to_string()[1..].parse::<u32>().unwrap();
+    let msb_first = matches!(&container_data.bit_order, Some(bit_order) if bit_order == "MsbFirst");
+
+    let mut layouts = vec![];
+    let mut used_bits = 0;
+
+    for field_data in fields_data {
+        let width = match &field_data.bits {
+            Some(bits) => bits.base10_parse::<u32>()?,
+            None => {
+                bail!("All the fields of a bitfield struct require the `bits` attribute" => field_data.field)
+            }
+        };
+
+        if field_data.align {
+            bail!("The `align` attribute requires a bit-level derive" => field_data.field);
+        }
+
+        validate_field_attributes(
+            field_data,
+            &[BITS_ATTR],
+            "Bitfield fields support only the `bits` attribute",
+        )?;
+
+        if used_bits + width > storage_bits {
+            bail!(format!("The bitfield fields exceed the {} storage bits", storage_bits) => field_data.field);
+        }
+
+        let offset = if msb_first {
+            storage_bits - used_bits - width
+        } else {
+            used_bits
+        };
+
+        layouts.push(BitfieldLayout { offset, width });
+
+        used_bits += width;
+    }
+
+    Ok(layouts)
+}
+
+// Applies to byte-level structs.
+//
+pub fn validate_no_bits(fields_data: &[NamedFieldData]) -> syn::Result<()> {
+    for field_data in fields_data {
+        if field_data.bits.is_some() {
+            bail!("The `bits` attribute requires a bitfield struct or a bit-level derive" => field_data.field);
+        }
+        if field_data.align {
+            bail!("The `align` attribute requires a bit-level derive" => field_data.field);
+        }
+    }
+
+    Ok(())
+}
+
+// Applies to bit-level structs.
+//
+pub fn validate_bit_level_fields(fields_data: &[NamedFieldData]) -> syn::Result<()> {
+    for field_data in fields_data {
+        validate_field_attributes(
+            field_data,
+            &[BITS_ATTR, ALIGN_ATTR],
+            "Bit-level fields support only the `bits` and `align` attributes",
+        )?;
+    }
+
+    Ok(())
+}
+
+// Bit-level fields are de/serialized only according to their bits, so the other attributes would be
+// silently ignored; attributes not explicitly supported are rejected.
+//
+fn validate_field_attributes(
+    field_data: &NamedFieldData,
+    supported_attributes: &[&str],
+    message: &str,
+) -> syn::Result<()> {
+    let unsupported_attribute = field_data.attributes.iter().find(|path| {
+        !supported_attributes
+            .iter()
+            .any(|attribute| path.is_ident(attribute))
+    });
+
+    if let Some(path) = unsupported_attribute {
+        bail!(message => path);
+    }
+
+    Ok(())
+}
+
+pub fn field_name_tokens(field: &Ident) -> TokenStream2 {
+    let field_name = LitStr::new(&field.to_string(), field.span());
+    quote! { #field_name }
+}
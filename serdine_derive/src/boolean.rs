@@ -0,0 +1,13 @@
+use proc_macro2::Ident;
+use quote::quote;
+
+type TokenStream2 = proc_macro2::TokenStream;
+
+// When only `strict_bool` is specified, the default layout is used.
+//
+pub fn bool_layout_tokens(bool_layout: &Option<Ident>) -> TokenStream2 {
+    match bool_layout {
+        Some(bool_layout) => quote! { serdine::boolean::BoolLayout::#bool_layout },
+        None => quote! { serdine::boolean::BoolLayout::U8 },
+    }
+}
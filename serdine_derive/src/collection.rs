@@ -1,12 +1,341 @@
-use syn::{DeriveInput, Expr, ExprLit, FieldsNamed, Ident, Lit, Meta, MetaNameValue};
+use proc_macro2::Span;
+use syn::{
+    parse_quote, Attribute, Data, DataStruct, DeriveInput, Expr, ExprLit, Field, FieldsNamed,
+    Ident, Index, Lit, LitInt, LitStr, Member, Meta, MetaList, MetaNameValue, NestedMeta, Path,
+    Type,
+};
 
 use crate::{
     bail::bail,
-    fields_data::{NamedFieldData, VariantData},
+    fields_data::{ContainerData, NamedFieldData, VariantData},
     target::Target,
 };
 
 const REPR_PATH: &str = "repr";
+const SERDINE_PATH: &str = "serdine";
+
+const WIDTH_ATTR: &str = "width";
+const ENDIAN_ATTR: &str = "endian";
+const ENCODING_ATTR: &str = "encoding";
+pub const BITS_ATTR: &str = "bits";
+const BITFIELD_ATTR: &str = "bitfield";
+const BIT_ORDER_ATTR: &str = "bit_order";
+pub const ALIGN_ATTR: &str = "align";
+const BITFLAGS_ATTR: &str = "bitflags";
+const PRESENCE_ATTR: &str = "presence";
+const NONE_VALUE_ATTR: &str = "none_value";
+const NONE_IF_ZEROED_ATTR: &str = "none_if_zeroed";
+const LEN_PREFIX_ATTR: &str = "len_prefix";
+const COUNT_ATTR: &str = "count";
+const BOOL_LAYOUT_ATTR: &str = "bool_layout";
+const STRICT_BOOL_ATTR: &str = "strict_bool";
+const CTX_ATTR: &str = "ctx";
+const IF_ATTR: &str = "if";
+const VERSION_ATTR: &str = "version";
+const SINCE_ATTR: &str = "since";
+const UNTIL_ATTR: &str = "until";
+const DEFAULT_ATTR: &str = "default";
+const ASSERT_ATTR: &str = "assert";
+const MESSAGE_ATTR: &str = "message";
+const POST_DESERIALIZE_ATTR: &str = "post_deserialize";
+const PRE_SERIALIZE_ATTR: &str = "pre_serialize";
+const CALC_ATTR: &str = "calc";
+const VERIFY_ATTR: &str = "verify";
+const AS_ATTR: &str = "as";
+const FROM_ATTR: &str = "from";
+const TRY_FROM_ATTR: &str = "try_from";
+const INTO_ATTR: &str = "into";
+const TRANSPARENT_ATTR: &str = "transparent";
+const REMOTE_ATTR: &str = "remote";
+const WITH_ATTR: &str = "with";
+const GETTER_ATTR: &str = "getter";
+
+const VARINT_ENCODINGS: &[&str] = &["uleb128", "sleb128", "zigzag", "vlq"];
+
+const BITFIELD_STORAGE_TYPES: &[&str] = &["u8", "u16", "u32", "u64"];
+
+const PRESENCE_TAG_TYPES: &[&str] = &["u8", "u16", "u32", "bool"];
+
+const LEN_PREFIX_TYPES: &[&str] = &["u8", "u16", "u32", "u64"];
+
+const MAX_WIDTH: u8 = 8;
+const MAX_BITS: u8 = 64;
+
+// ////////////////////////////////////////////////////////////////////////////////
+// CONTAINER
+// ////////////////////////////////////////////////////////////////////////////////
+
+pub fn collect_container_data(ast: &DeriveInput) -> syn::Result<ContainerData> {
+    let mut container_data = ContainerData::default();
+
+    for attr in &ast.attrs {
+        if !attr.path.is_ident(SERDINE_PATH) {
+            continue;
+        }
+
+        if let Meta::List(MetaList { nested, .. }) = attr.parse_meta()? {
+            for nested_meta in &nested {
+                parse_container_attribute(&mut container_data, nested_meta)?;
+            }
+        }
+    }
+
+    if container_data.bit_order.is_some() && container_data.bitfield.is_none() {
+        bail!(format!(
+            "The `{}` attribute requires `{}`",
+            BIT_ORDER_ATTR, BITFIELD_ATTR
+        ));
+    }
+
+    if container_data.bitfield.is_some() && !matches!(ast.data, Data::Struct(_)) {
+        bail!(format!(
+            "The `{}` attribute requires a struct",
+            BITFIELD_ATTR
+        ));
+    }
+
+    if container_data.bitfield.is_some() && container_data.bitflags.is_some() {
+        bail!(format!(
+            "The `{}` and `{}` attributes can't be combined",
+            BITFIELD_ATTR, BITFLAGS_ATTR
+        ));
+    }
+
+    let is_plain_struct = matches!(ast.data, Data::Struct(_))
+        && container_data.bitfield.is_none()
+        && container_data.bitflags.is_none();
+
+    let plain_struct_attributes = [
+        (CTX_ATTR, container_data.ctx.is_some()),
+        (VERSION_ATTR, container_data.version.is_some()),
+        (ASSERT_ATTR, container_data.assertion.is_some()),
+        (
+            POST_DESERIALIZE_ATTR,
+            container_data.post_deserialize.is_some(),
+        ),
+        (PRE_SERIALIZE_ATTR, container_data.pre_serialize.is_some()),
+        (REMOTE_ATTR, container_data.remote.is_some()),
+    ];
+
+    for (attribute, is_set) in plain_struct_attributes {
+        if is_set && !is_plain_struct {
+            bail!(format!(
+                "The `{}` attribute requires a struct, without `{}`/`{}`",
+                attribute, BITFIELD_ATTR, BITFLAGS_ATTR
+            ));
+        }
+    }
+
+    if container_data.from.is_some() && container_data.try_from.is_some() {
+        bail!(format!(
+            "The `{}` and `{}` attributes can't be combined",
+            FROM_ATTR, TRY_FROM_ATTR
+        ));
+    }
+
+    let has_conversion = container_data.from.is_some()
+        || container_data.try_from.is_some()
+        || container_data.into.is_some();
+
+    if has_conversion && (container_data.bitfield.is_some() || container_data.bitflags.is_some()) {
+        bail!(format!(
+            "The `{}`/`{}`/`{}` attributes can't be combined with `{}`/`{}`",
+            FROM_ATTR, TRY_FROM_ATTR, INTO_ATTR, BITFIELD_ATTR, BITFLAGS_ATTR
+        ));
+    }
+
+    // Converted types are de/serialized via the intermediate type, so the attributes that apply to
+    // the fields of the struct would be silently ignored.
+    //
+    let has_struct_attributes = container_data.ctx.is_some()
+        || container_data.version.is_some()
+        || container_data.assertion.is_some()
+        || container_data.post_deserialize.is_some()
+        || container_data.pre_serialize.is_some();
+
+    if has_conversion && has_struct_attributes {
+        bail!(format!(
+            "The `{}`/`{}`/`{}` attributes can't be combined with `{}`, `{}`, `{}`, `{}` and `{}`",
+            FROM_ATTR,
+            TRY_FROM_ATTR,
+            INTO_ATTR,
+            CTX_ATTR,
+            VERSION_ATTR,
+            ASSERT_ATTR,
+            POST_DESERIALIZE_ATTR,
+            PRE_SERIALIZE_ATTR
+        ));
+    }
+
+    if let Data::Struct(DataStruct { fields, .. }) = &ast.data {
+        if has_conversion {
+            if let Some(attr) = fields.iter().find_map(find_field_attribute) {
+                bail!(format!(
+                    "The fields of a struct with `{}`/`{}`/`{}` can't have attributes",
+                    FROM_ATTR, TRY_FROM_ATTR, INTO_ATTR
+                ) => attr);
+            }
+        }
+    }
+
+    // Transparent types have no representation of their own.
+    //
+    let has_representation_attributes = container_data.bitfield.is_some()
+        || container_data.bitflags.is_some()
+        || container_data.ctx.is_some()
+        || container_data.version.is_some()
+        || container_data.assertion.is_some()
+        || container_data.post_deserialize.is_some()
+        || container_data.pre_serialize.is_some()
+        || has_conversion;
+
+    if container_data.transparent && has_representation_attributes {
+        bail!(format!(
+            "The `{}` attribute can't be combined with other container attributes",
+            TRANSPARENT_ATTR
+        ));
+    }
+
+    // The generated functions have a fixed signature, and de/serialize the remote type directly.
+    //
+    let has_remote_incompatible_attributes =
+        container_data.ctx.is_some() || container_data.transparent || has_conversion;
+
+    if container_data.remote.is_some() && has_remote_incompatible_attributes {
+        bail!(format!(
+            "The `{}` attribute can't be combined with `{}`, `{}` and the conversion attributes",
+            REMOTE_ATTR, CTX_ATTR, TRANSPARENT_ATTR
+        ));
+    }
+
+    if container_data.message.is_some() && container_data.assertion.is_none() {
+        bail!(format!(
+            "The `{}` attribute requires `{}`",
+            MESSAGE_ATTR, ASSERT_ATTR
+        ));
+    }
+
+    Ok(container_data)
+}
+
+fn parse_container_attribute(
+    container_data: &mut ContainerData,
+    nested_meta: &NestedMeta,
+) -> syn::Result<()> {
+    match nested_meta {
+        NestedMeta::Meta(Meta::NameValue(MetaNameValue { path, lit, .. })) => {
+            match attribute_name(path).as_str() {
+                BITFIELD_ATTR => container_data.bitfield = Some(parse_bitfield_storage(lit)?),
+                BIT_ORDER_ATTR => container_data.bit_order = Some(parse_bit_order(lit)?),
+                BITFLAGS_ATTR => container_data.bitflags = Some(parse_unknown_bits(lit)?),
+                CTX_ATTR => container_data.ctx = Some(parse_type(lit, CTX_ATTR)?),
+                VERSION_ATTR => container_data.version = Some(parse_expression(lit, VERSION_ATTR)?),
+                ASSERT_ATTR => container_data.assertion = Some(parse_expression(lit, ASSERT_ATTR)?),
+                MESSAGE_ATTR => container_data.message = Some(parse_message(lit)?),
+                POST_DESERIALIZE_ATTR => {
+                    container_data.post_deserialize =
+                        Some(parse_expression(lit, POST_DESERIALIZE_ATTR)?)
+                }
+                PRE_SERIALIZE_ATTR => {
+                    container_data.pre_serialize = Some(parse_expression(lit, PRE_SERIALIZE_ATTR)?)
+                }
+                FROM_ATTR => container_data.from = Some(parse_type(lit, FROM_ATTR)?),
+                TRY_FROM_ATTR => container_data.try_from = Some(parse_type(lit, TRY_FROM_ATTR)?),
+                INTO_ATTR => container_data.into = Some(parse_type(lit, INTO_ATTR)?),
+                REMOTE_ATTR => container_data.remote = Some(parse_path(lit, REMOTE_ATTR)?),
+                _ => bail!("Unrecognized serdine attribute" => path),
+            }
+        }
+        NestedMeta::Meta(Meta::Path(path)) => match attribute_name(path).as_str() {
+            TRANSPARENT_ATTR => container_data.transparent = true,
+            _ => bail!("Unrecognized serdine attribute" => path),
+        },
+        _ => bail!("Unrecognized serdine attribute format" => nested_meta),
+    }
+
+    Ok(())
+}
+
+fn parse_bitfield_storage(lit: &Lit) -> syn::Result<Ident> {
+    if let Lit::Str(lit_str) = lit {
+        let storage_type = lit_str.value();
+
+        if BITFIELD_STORAGE_TYPES.contains(&storage_type.as_str()) {
+            return Ok(Ident::new(&storage_type, lit_str.span()));
+        }
+    }
+
+    bail!(format!("The `{}` attribute requires one of: {}", BITFIELD_ATTR, BITFIELD_STORAGE_TYPES.join(", ")) => lit)
+}
+
+fn parse_unknown_bits(lit: &Lit) -> syn::Result<Ident> {
+    if let Lit::Str(lit_str) = lit {
+        match lit_str.value().as_str() {
+            "reject" => return Ok(Ident::new("Reject", Span::call_site())),
+            "truncate" => return Ok(Ident::new("Truncate", Span::call_site())),
+            "retain" => return Ok(Ident::new("Retain", Span::call_site())),
+            _ => {}
+        }
+    }
+
+    bail!(format!("The `{}` attribute requires \"reject\", \"truncate\" or \"retain\"", BITFLAGS_ATTR) => lit)
+}
+
+fn parse_bit_order(lit: &Lit) -> syn::Result<Ident> {
+    if let Lit::Str(lit_str) = lit {
+        match lit_str.value().as_str() {
+            "lsb" => return Ok(Ident::new("LsbFirst", Span::call_site())),
+            "msb" => return Ok(Ident::new("MsbFirst", Span::call_site())),
+            _ => {}
+        }
+    }
+
+    bail!(format!("The `{}` attribute requires \"lsb\" or \"msb\"", BIT_ORDER_ATTR) => lit)
+}
+
+// Returns the first serdine attribute of the field, if any.
+//
+fn find_field_attribute(field: &Field) -> Option<&Attribute> {
+    field.attrs.iter().find(|attr| {
+        attr.path.is_ident(SERDINE_PATH)
+            || attr
+                .path
+                .is_ident(Target::ForSerialization.attribute_name())
+            || attr
+                .path
+                .is_ident(Target::ForDeserialization.attribute_name())
+    })
+}
+
+// Returns the member and type of the field of a transparent struct.
+//
+pub fn find_transparent_field(ast: &DeriveInput) -> syn::Result<(Member, Type)> {
+    if let Data::Struct(DataStruct { fields, .. }) = &ast.data {
+        if fields.len() == 1 {
+            // The field is necessarily found.
+            //
+            let field = fields.iter().next().unwrap();
+
+            // The field is de/serialized as is, so its attributes would be silently ignored.
+            //
+            if let Some(attr) = find_field_attribute(field) {
+                bail!(format!("The field of a `{}` struct can't have attributes", TRANSPARENT_ATTR) => attr);
+            }
+
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(0)),
+            };
+
+            return Ok((member, field.ty.clone()));
+        }
+    }
+
+    bail!(format!(
+        "The `{}` attribute requires a struct with a single field",
+        TRANSPARENT_ATTR
+    ))
+}
 
 // ////////////////////////////////////////////////////////////////////////////////
 // STRUCT WITH NAMED FIELDS
@@ -21,7 +350,7 @@ pub fn collect_named_fields_data(
     for field in &fields.named {
         // Fields are named, so an ident is necessarily found.
         //
-        let mut field_data = NamedFieldData::new(field.ident.clone().unwrap());
+        let mut field_data = NamedFieldData::new(field.ident.clone().unwrap(), field.ty.clone());
 
         for attr in &field.attrs {
             let attr_meta = match attr.parse_meta() {
@@ -29,13 +358,14 @@ pub fn collect_named_fields_data(
                 Err(error) => bail!(error),
             };
 
-            if let Meta::NameValue(MetaNameValue {
-                ref path, ref lit, ..
-            }) = attr_meta
-            {
+            match attr_meta {
                 // There are different approaches; all a bit odd, but avoid duplicating the rest.
                 //
-                if path.is_ident(target.attribute_name()) {
+                Meta::NameValue(MetaNameValue {
+                    ref path, ref lit, ..
+                }) if path.is_ident(target.attribute_name()) => {
+                    field_data.attributes.push(path.clone());
+
                     if let Lit::Str(lit_val) = lit {
                         target.set_serialization_fn(&mut field_data, lit_val.to_owned());
                     } else {
@@ -45,15 +375,358 @@ pub fn collect_named_fields_data(
                         ));
                     }
                 }
+                Meta::List(MetaList {
+                    ref path,
+                    ref nested,
+                    ..
+                }) if path.is_ident(SERDINE_PATH) => {
+                    for nested_meta in nested {
+                        parse_field_attribute(&mut field_data, nested_meta)?;
+                    }
+                }
+                _ => {}
             }
         }
 
+        validate_field_data(&field_data)?;
+
         fields_data.push(field_data);
     }
 
+    // Defaults apply when the input ends, so only trailing fields can have them.
+    //
+    let first_default_index = fields_data
+        .iter()
+        .position(|field_data| field_data.default.is_some());
+
+    if let Some(first_default_index) = first_default_index {
+        for field_data in &fields_data[first_default_index..] {
+            if field_data.default.is_none() {
+                bail!(format!("Fields following a field with `{}` must have it too", DEFAULT_ATTR) => field_data.field);
+            }
+        }
+    }
+
     Ok(fields_data)
 }
 
+fn parse_field_attribute(
+    field_data: &mut NamedFieldData,
+    nested_meta: &NestedMeta,
+) -> syn::Result<()> {
+    match nested_meta {
+        NestedMeta::Meta(Meta::NameValue(MetaNameValue { path, lit, .. })) => {
+            field_data.attributes.push(path.clone());
+
+            match attribute_name(path).as_str() {
+                WIDTH_ATTR => field_data.width = Some(parse_width(lit)?),
+                ENDIAN_ATTR => field_data.endianness = Some(parse_endianness(lit)?),
+                ENCODING_ATTR => field_data.encoding = Some(parse_encoding(lit)?),
+                BITS_ATTR => field_data.bits = Some(parse_bits(lit)?),
+                BIT_ORDER_ATTR => field_data.bit_order = Some(parse_bit_order(lit)?),
+                PRESENCE_ATTR => field_data.presence = Some(parse_presence(lit)?),
+                NONE_VALUE_ATTR => field_data.none_value = Some(parse_none_value(lit)?),
+                LEN_PREFIX_ATTR => field_data.len_prefix = Some(parse_len_prefix(lit)?),
+                COUNT_ATTR => field_data.count = Some(parse_expression(lit, COUNT_ATTR)?),
+                BOOL_LAYOUT_ATTR => field_data.bool_layout = Some(parse_bool_layout(lit)?),
+                CTX_ATTR => field_data.ctx = Some(parse_expression(lit, CTX_ATTR)?),
+                IF_ATTR => field_data.condition = Some(parse_expression(lit, IF_ATTR)?),
+                SINCE_ATTR => field_data.since = Some(parse_version(lit, SINCE_ATTR)?),
+                UNTIL_ATTR => field_data.until = Some(parse_version(lit, UNTIL_ATTR)?),
+                DEFAULT_ATTR => field_data.default = Some(parse_expression(lit, DEFAULT_ATTR)?),
+                ASSERT_ATTR => field_data.assertion = Some(parse_expression(lit, ASSERT_ATTR)?),
+                MESSAGE_ATTR => field_data.message = Some(parse_message(lit)?),
+                CALC_ATTR => field_data.calc = Some(parse_expression(lit, CALC_ATTR)?),
+                AS_ATTR => field_data.as_type = Some(parse_type(lit, AS_ATTR)?),
+                WITH_ATTR => field_data.with = Some(parse_path(lit, WITH_ATTR)?),
+                GETTER_ATTR => field_data.getter = Some(parse_path(lit, GETTER_ATTR)?),
+                _ => bail!("Unrecognized serdine attribute" => path),
+            }
+        }
+        NestedMeta::Meta(Meta::Path(path)) => {
+            field_data.attributes.push(path.clone());
+
+            match attribute_name(path).as_str() {
+                ALIGN_ATTR => field_data.align = true,
+                NONE_IF_ZEROED_ATTR => field_data.none_if_zeroed = true,
+                STRICT_BOOL_ATTR => field_data.strict_bool = true,
+                VERIFY_ATTR => field_data.verify = true,
+                DEFAULT_ATTR => {
+                    field_data.default = Some(parse_quote! { std::default::Default::default() })
+                }
+                _ => bail!("Unrecognized serdine attribute" => path),
+            }
+        }
+        _ => bail!("Unrecognized serdine attribute format" => nested_meta),
+    }
+
+    Ok(())
+}
+
+fn validate_field_data(field_data: &NamedFieldData) -> syn::Result<()> {
+    let has_custom_fn =
+        field_data.deserialization_fn.is_some() || field_data.serialization_fn.is_some();
+    let has_width = field_data.width.is_some() || field_data.endianness.is_some();
+    let has_encoding = field_data.encoding.is_some();
+    let has_bits = field_data.bits.is_some();
+    let has_bit_order = field_data.bit_order.is_some();
+    let has_option_encoding = field_data.presence.is_some()
+        || field_data.none_value.is_some()
+        || field_data.none_if_zeroed
+        || field_data.condition.is_some();
+    let has_collection_encoding = field_data.len_prefix.is_some() || field_data.count.is_some();
+    let has_bool_encoding = field_data.bool_layout.is_some() || field_data.strict_bool;
+    let has_ctx = field_data.ctx.is_some();
+    let has_as_type = field_data.as_type.is_some();
+    let has_with = field_data.with.is_some();
+
+    let representations_count = [
+        has_custom_fn,
+        has_width,
+        has_encoding,
+        has_bits,
+        has_bit_order,
+        has_option_encoding,
+        has_collection_encoding,
+        has_bool_encoding,
+        has_ctx,
+        has_as_type,
+        has_with,
+    ]
+    .iter()
+    .filter(|is_set| **is_set)
+    .count();
+
+    if representations_count > 1 {
+        bail!("Only one of custom de/serialization functions, `width`/`endian`, `encoding`, `bits`, `bit_order`, the `Option` encodings, the collection encodings, the bool encodings, `ctx`, `as` and `with` can be specified" => field_data.field);
+    }
+
+    if field_data.len_prefix.is_some() && field_data.count.is_some() {
+        bail!(format!("Only one of `{}` and `{}` can be specified", LEN_PREFIX_ATTR, COUNT_ATTR) => field_data.field);
+    }
+
+    let option_encodings_count = [
+        field_data.presence.is_some(),
+        field_data.none_value.is_some(),
+        field_data.none_if_zeroed,
+        field_data.condition.is_some(),
+    ]
+    .iter()
+    .filter(|is_set| **is_set)
+    .count();
+
+    if option_encodings_count > 1 {
+        bail!(format!("Only one of `{}`, `{}`, `{}` and `{}` can be specified", PRESENCE_ATTR, NONE_VALUE_ATTR, NONE_IF_ZEROED_ATTR, IF_ATTR) => field_data.field);
+    }
+
+    if field_data.verify && field_data.calc.is_none() {
+        bail!(format!("The `{}` attribute requires `{}`", VERIFY_ATTR, CALC_ATTR) => field_data.field);
+    }
+
+    if field_data.message.is_some() && field_data.assertion.is_none() {
+        bail!(format!("The `{}` attribute requires `{}`", MESSAGE_ATTR, ASSERT_ATTR) => field_data.field);
+    }
+
+    if let (Some(since), Some(until)) = (&field_data.since, &field_data.until) {
+        // Both have been validated on parsing.
+        //
+        if since.base10_parse::<u64>()? > until.base10_parse::<u64>()? {
+            bail!(format!("The `{}` version can't be greater than the `{}` one", SINCE_ATTR, UNTIL_ATTR) => field_data.field);
+        }
+    }
+
+    Ok(())
+}
+
+// Versioned fields require the container `version` attribute; this is checked separately, since
+// the fields data is collected independently of the container data.
+//
+pub fn validate_versioned_fields(
+    container_data: &ContainerData,
+    fields_data: &[NamedFieldData],
+) -> syn::Result<()> {
+    if container_data.version.is_some() {
+        return Ok(());
+    }
+
+    for field_data in fields_data {
+        if field_data.since.is_some() || field_data.until.is_some() {
+            bail!(format!("The `{}`/`{}` attributes require the container `{}` attribute", SINCE_ATTR, UNTIL_ATTR, VERSION_ATTR) => field_data.field);
+        }
+    }
+
+    Ok(())
+}
+
+// Same as the versioned fields; getters apply only to the fields of remote types.
+//
+pub fn validate_remote_fields(
+    container_data: &ContainerData,
+    fields_data: &[NamedFieldData],
+) -> syn::Result<()> {
+    if container_data.remote.is_some() {
+        return Ok(());
+    }
+
+    for field_data in fields_data {
+        if field_data.getter.is_some() {
+            bail!(format!("The `{}` attribute requires the container `{}` attribute", GETTER_ATTR, REMOTE_ATTR) => field_data.field);
+        }
+    }
+
+    Ok(())
+}
+
+fn attribute_name(path: &Path) -> String {
+    path.get_ident().map(Ident::to_string).unwrap_or_default()
+}
+
+fn parse_width(lit: &Lit) -> syn::Result<LitInt> {
+    if let Lit::Int(lit_int) = lit {
+        if let Ok(width) = lit_int.base10_parse::<u8>() {
+            if width >= 1 && width <= MAX_WIDTH {
+                return Ok(lit_int.clone());
+            }
+        }
+    }
+
+    bail!(format!("The `{}` attribute requires an integer between 1 and {}", WIDTH_ATTR, MAX_WIDTH) => lit)
+}
+
+fn parse_version(lit: &Lit, attribute: &str) -> syn::Result<LitInt> {
+    if let Lit::Int(lit_int) = lit {
+        if lit_int.base10_parse::<u64>().is_ok() {
+            return Ok(lit_int.clone());
+        }
+    }
+
+    bail!(format!("The `{}` attribute requires a non-negative integer", attribute) => lit)
+}
+
+fn parse_endianness(lit: &Lit) -> syn::Result<Ident> {
+    if let Lit::Str(lit_str) = lit {
+        match lit_str.value().as_str() {
+            "little" => return Ok(Ident::new("Little", Span::call_site())),
+            "big" => return Ok(Ident::new("Big", Span::call_site())),
+            _ => {}
+        }
+    }
+
+    bail!(format!("The `{}` attribute requires \"little\" or \"big\"", ENDIAN_ATTR) => lit)
+}
+
+fn parse_bits(lit: &Lit) -> syn::Result<LitInt> {
+    if let Lit::Int(lit_int) = lit {
+        if let Ok(bits) = lit_int.base10_parse::<u8>() {
+            if bits >= 1 && bits <= MAX_BITS {
+                return Ok(lit_int.clone());
+            }
+        }
+    }
+
+    bail!(format!("The `{}` attribute requires an integer between 1 and {}", BITS_ATTR, MAX_BITS) => lit)
+}
+
+fn parse_encoding(lit: &Lit) -> syn::Result<Ident> {
+    if let Lit::Str(lit_str) = lit {
+        let encoding = lit_str.value();
+
+        if VARINT_ENCODINGS.contains(&encoding.as_str()) {
+            return Ok(Ident::new(&encoding, lit_str.span()));
+        }
+    }
+
+    bail!(format!("The `{}` attribute requires one of: {}", ENCODING_ATTR, VARINT_ENCODINGS.join(", ")) => lit)
+}
+
+fn parse_presence(lit: &Lit) -> syn::Result<Ident> {
+    if let Lit::Str(lit_str) = lit {
+        let tag_type = lit_str.value();
+
+        if PRESENCE_TAG_TYPES.contains(&tag_type.as_str()) {
+            return Ok(Ident::new(&tag_type, lit_str.span()));
+        }
+    }
+
+    bail!(format!("The `{}` attribute requires one of: {}", PRESENCE_ATTR, PRESENCE_TAG_TYPES.join(", ")) => lit)
+}
+
+// Values that are not literals (e.g. negative numbers) can't be expressed in attributes, so they
+// are accepted as strings.
+//
+fn parse_none_value(lit: &Lit) -> syn::Result<Expr> {
+    match lit {
+        Lit::Str(lit_str) => lit_str.parse(),
+        Lit::Int(_) | Lit::Float(_) | Lit::Bool(_) | Lit::Byte(_) | Lit::Char(_) => {
+            Ok(Expr::Lit(ExprLit {
+                attrs: vec![],
+                lit: lit.clone(),
+            }))
+        }
+        _ => {
+            bail!(format!("The `{}` attribute requires a literal, or an expression string", NONE_VALUE_ATTR) => lit)
+        }
+    }
+}
+
+fn parse_len_prefix(lit: &Lit) -> syn::Result<Ident> {
+    if let Lit::Str(lit_str) = lit {
+        let prefix_type = lit_str.value();
+
+        if LEN_PREFIX_TYPES.contains(&prefix_type.as_str()) {
+            return Ok(Ident::new(&prefix_type, lit_str.span()));
+        }
+    }
+
+    bail!(format!("The `{}` attribute requires one of: {}", LEN_PREFIX_ATTR, LEN_PREFIX_TYPES.join(", ")) => lit)
+}
+
+fn parse_bool_layout(lit: &Lit) -> syn::Result<Ident> {
+    if let Lit::Str(lit_str) = lit {
+        match lit_str.value().as_str() {
+            "u8" => return Ok(Ident::new("U8", Span::call_site())),
+            "u16" => return Ok(Ident::new("U16", Span::call_site())),
+            "u32" => return Ok(Ident::new("U32", Span::call_site())),
+            "u8_ff" => return Ok(Ident::new("U8Ff", Span::call_site())),
+            _ => {}
+        }
+    }
+
+    bail!(format!("The `{}` attribute requires \"u8\", \"u16\", \"u32\" or \"u8_ff\"", BOOL_LAYOUT_ATTR) => lit)
+}
+
+fn parse_type(lit: &Lit, attribute: &str) -> syn::Result<Type> {
+    if let Lit::Str(lit_str) = lit {
+        return lit_str.parse();
+    }
+
+    bail!(format!("The `{}` attribute requires a type string", attribute) => lit)
+}
+
+fn parse_path(lit: &Lit, attribute: &str) -> syn::Result<Path> {
+    if let Lit::Str(lit_str) = lit {
+        return lit_str.parse();
+    }
+
+    bail!(format!("The `{}` attribute requires a path string", attribute) => lit)
+}
+
+fn parse_message(lit: &Lit) -> syn::Result<LitStr> {
+    if let Lit::Str(lit_str) = lit {
+        return Ok(lit_str.clone());
+    }
+
+    bail!(format!("The `{}` attribute requires a string literal", MESSAGE_ATTR) => lit)
+}
+
+fn parse_expression(lit: &Lit, attribute: &str) -> syn::Result<Expr> {
+    if let Lit::Str(lit_str) = lit {
+        return lit_str.parse();
+    }
+
+    bail!(format!("The `{}` attribute requires an expression string", attribute) => lit)
+}
+
 // ////////////////////////////////////////////////////////////////////////////////
 // ENUMS
 // ////////////////////////////////////////////////////////////////////////////////
@@ -97,7 +770,10 @@ pub fn collect_variants_data(data_enum: &syn::DataEnum) -> syn::Result<Vec<Varia
             }),
         )) = &variant.discriminant
         {
-            lit_int.clone()
+            // The suffix (e.g. `1u8`) is dropped, since the discriminant is matched against values
+            // of different types (the representation, and the bits of bitfields).
+            //
+            LitInt::new(lit_int.base10_digits(), lit_int.span())
         } else {
             bail!(format!("'{}' variant discriminant not found!", ident))
         };
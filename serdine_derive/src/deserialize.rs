@@ -1,11 +1,26 @@
-use crate::collection::{collect_variants_data, find_type_numeric_repr};
-use crate::fields_data::{NamedFieldData, VariantData};
+use crate::assertion::assertion_tokens;
+use crate::bitfield::{
+    compute_bitfield_layouts, field_name_tokens, validate_no_bits, BitfieldLayout,
+};
+use crate::boolean::bool_layout_tokens;
+use crate::collection::{
+    collect_container_data, collect_variants_data, find_transparent_field, find_type_numeric_repr,
+    validate_remote_fields, validate_versioned_fields,
+};
+use crate::expressions::{
+    calc_bindings, field_access_tokens, locals_bindings, replace_self, value_tokens,
+};
+use crate::fields_data::{ContainerData, NamedFieldData, VariantData};
+use crate::odd_width::{endianness_tokens, width_tokens};
+use crate::remote::remote_fields_checks;
 use crate::target::Target::ForDeserialization;
+use crate::version::version_condition_tokens;
 use crate::{bail::bail, collection::collect_named_fields_data};
 
-use proc_macro2::Ident;
-use quote::quote;
-use syn::{self, parse2, Data, DataStruct, DeriveInput, Fields};
+use proc_macro2::{Ident, Span};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{self, parse2, Data, DataStruct, DeriveInput, Fields, LitStr, Member, Type, Visibility};
 
 type TokenStream2 = proc_macro2::TokenStream;
 
@@ -13,21 +28,60 @@ pub(crate) fn impl_deserialize(input: impl Into<TokenStream2>) -> syn::Result<To
     let ast: DeriveInput = parse2(input.into())?;
     let type_name = &ast.ident;
 
-    let deserialize_impl = match &ast.data {
-        Data::Struct(DataStruct { fields, .. }) => match fields {
-            Fields::Named(fields) => {
-                let named_fields_data = collect_named_fields_data(fields, ForDeserialization)?;
-                impl_trait_with_named_fields(type_name, named_fields_data)?
+    let container_data = collect_container_data(&ast)?;
+
+    let stored_type = container_data
+        .from
+        .as_ref()
+        .or(container_data.try_from.as_ref());
+
+    let deserialize_impl = if container_data.transparent {
+        let (member, field_type) = find_transparent_field(&ast)?;
+        impl_trait_transparent(type_name, &member, &field_type)?
+    } else if let Some(stored_type) = stored_type {
+        impl_trait_with_conversion(type_name, stored_type)?
+    } else if let Some(unknown_bits) = &container_data.bitflags {
+        impl_trait_with_bitflags(type_name, unknown_bits)?
+    } else {
+        match &ast.data {
+            Data::Struct(DataStruct { fields, .. }) => match fields {
+                Fields::Named(fields) => {
+                    let named_fields_data = collect_named_fields_data(fields, ForDeserialization)?;
+                    validate_versioned_fields(&container_data, &named_fields_data)?;
+                    validate_remote_fields(&container_data, &named_fields_data)?;
+
+                    if let Some(storage_type) = &container_data.bitfield {
+                        let layouts = compute_bitfield_layouts(
+                            storage_type,
+                            &container_data,
+                            &named_fields_data,
+                        )?;
+                        impl_trait_with_bitfield(
+                            type_name,
+                            storage_type,
+                            named_fields_data,
+                            layouts,
+                        )?
+                    } else {
+                        validate_no_bits(&named_fields_data)?;
+                        impl_trait_with_named_fields(
+                            type_name,
+                            &ast.vis,
+                            &container_data,
+                            named_fields_data,
+                        )?
+                    }
+                }
+                Fields::Unnamed(_) => bail!("Unnamed fields not supported!"),
+                Fields::Unit => bail!("Unit fields not supported!"),
+            },
+            Data::Enum(data_enum) => {
+                let enum_repr = find_type_numeric_repr(&ast)?;
+                let variants_data = collect_variants_data(data_enum)?;
+                impl_trait_with_enum_variants(type_name, enum_repr, variants_data)?
             }
-            Fields::Unnamed(_) => bail!("Unnamed fields not supported!"),
-            Fields::Unit => bail!("Unit fields not supported!"),
-        },
-        Data::Enum(data_enum) => {
-            let enum_repr = find_type_numeric_repr(&ast)?;
-            let variants_data = collect_variants_data(data_enum)?;
-            impl_trait_with_enum_variants(type_name, enum_repr, variants_data)?
+            Data::Union(_) => bail!("Unions not supported!"),
         }
-        Data::Union(_) => bail!("Unions not supported!"),
     };
 
     Ok(quote!(
@@ -37,23 +91,270 @@ pub(crate) fn impl_deserialize(input: impl Into<TokenStream2>) -> syn::Result<To
 
 fn impl_trait_with_named_fields(
     type_name: &Ident,
+    vis: &Visibility,
+    container_data: &ContainerData,
     fields_data: Vec<NamedFieldData>,
 ) -> syn::Result<TokenStream2> {
-    let fields_deserialization = fields_data.iter().map(
-        |NamedFieldData {
-             field,
-             deserialization_fn,
-             ..
-         }| {
-            let quoted_deserialization_fn = if let Some(deserialization_fn) = deserialization_fn {
-                let deserialization_fn =
-                    Ident::new(&deserialization_fn.value(), deserialization_fn.span());
-                quote! { #deserialization_fn(&mut r)? }
-            } else {
-                quote! { serdine::Deserialize::deserialize(&mut r)? }
+    let ctx = container_data.ctx.as_ref();
+
+    let fields_deserialization = fields_data.iter().enumerate().map(|(index, field_data)| {
+        let field = &field_data.field;
+        let field_type = &field_data.field_type;
+        let previous_fields_data = &fields_data[..index];
+        let mut quoted_deserialization_fn =
+            field_deserialization(field_data, previous_fields_data, ctx.is_some());
+
+        // Fields that may be not read (absent from the version, or at the end of the input) are
+        // paired with a flag, if it's needed by the checks, which are skipped for such fields.
+        //
+        let read_flag = read_flag_ident(field_data, container_data);
+        let with_read_flag = |value: TokenStream2, is_read: bool| match read_flag {
+            Some(_) => quote! { (#value, #is_read) },
+            None => value,
+        };
+
+        // The reader is shadowed by the tracker; the closure scopes the errors of the field.
+        //
+        if let Some(default) = &field_data.default {
+            let value = with_read_flag(quote! { value }, true);
+            let default = with_read_flag(quote! { #default }, false);
+
+            quoted_deserialization_fn = quote! {
+                {
+                    let mut r = serdine::eof::EofTracker::new(&mut r);
+                    let result = (|| -> Result<#field_type, std::io::Error> {
+                        let value = #quoted_deserialization_fn;
+                        Ok(value)
+                    })();
+
+                    match r.unless_eof(result)? {
+                        Some(value) => #value,
+                        None => #default,
+                    }
+                }
             };
+        } else {
+            quoted_deserialization_fn = with_read_flag(quoted_deserialization_fn, true);
+        }
+
+        // Fields not present in the version take the default value.
+        //
+        let version = container_data.version.as_ref();
 
-            quote! { let #field = #quoted_deserialization_fn; }
+        if let Some(condition) = version_condition_tokens(version, field_data) {
+            // A condition is returned only if the version is set.
+            //
+            let bindings = locals_bindings(version.unwrap(), previous_fields_data);
+            let default = with_read_flag(quote! { std::default::Default::default() }, false);
+
+            quoted_deserialization_fn = quote! {
+                if { #bindings #condition } {
+                    #quoted_deserialization_fn
+                } else {
+                    #default
+                }
+            };
+        }
+
+        let field_deserialization = match &read_flag {
+            Some(read_flag) => quote! {
+                let (#field, #read_flag): (#field_type, bool) = #quoted_deserialization_fn;
+            },
+            None => quote! { let #field: #field_type = #quoted_deserialization_fn; },
+        };
+
+        let assertion = field_data.assertion.as_ref().map(|assertion| {
+            let assertion = assertion_tokens(assertion, &field_data.message, Some(field));
+            match &read_flag {
+                Some(read_flag) => quote! { if #read_flag { #assertion } },
+                None => assertion,
+            }
+        });
+
+        quote! {
+            #field_deserialization
+            #assertion
+        }
+    });
+
+    let container_assertion = container_data
+        .assertion
+        .as_ref()
+        .map(|assertion| assertion_tokens(assertion, &container_data.message, None));
+
+    let self_fields = fields_data
+        .iter()
+        .map(|NamedFieldData { field, .. }| quote! { #field, });
+
+    // Calculated values are verified once all the fields are available.
+    //
+    let calc_verifications = fields_data.iter().filter(|field_data| field_data.verify).map(
+        |field_data| {
+            let NamedFieldData {
+                field,
+                field_type,
+                calc,
+                ..
+            } = field_data;
+
+            // Fields with `verify` have been validated to have `calc`.
+            //
+            let calc = calc.as_ref().unwrap();
+            let bindings = calc_bindings(field, calc, &fields_data);
+            let field_name = field_name_tokens(field);
+            let field_access = field_access_tokens(field_data);
+            let verification = quote! {
+                {
+                    #bindings
+                    let calculated: #field_type = #calc;
+                    if #field_access != calculated {
+                        return Err(serdine::Error::CalculatedValueMismatch { field: #field_name }.into());
+                    }
+                }
+            };
+            let verification = match read_flag_ident(field_data, container_data) {
+                Some(read_flag) => quote! { if #read_flag { #verification } },
+                None => verification,
+            };
+            replace_self(verification, &Ident::new("result", Span::call_site()))
+        },
+    );
+
+    // The hook receives the context, if any, like the custom deserialization functions.
+    //
+    let (let_result, post_deserialize) = match &container_data.post_deserialize {
+        Some(post_deserialize) => {
+            let ctx_arg = ctx.map(|_| quote! { , ctx });
+            (
+                quote! { let mut result },
+                quote! { #post_deserialize(&mut result #ctx_arg)?; },
+            )
+        }
+        None => (quote! { let result }, quote! {}),
+    };
+
+    // Remote types are constructed directly; if some fields are accessed via getters (e.g. because
+    // they're private), via the conversion from the mirror instead.
+    //
+    let has_getters = fields_data
+        .iter()
+        .any(|field_data| field_data.getter.is_some());
+
+    let construction = match &container_data.remote {
+        Some(remote) if has_getters => quote! {
+            #let_result: #remote = std::convert::From::from(Self {
+                #(#self_fields)*
+            });
+        },
+        Some(remote) => quote! {
+            #let_result = #remote {
+                #(#self_fields)*
+            };
+        },
+        None => quote! {
+            #let_result = Self {
+                #(#self_fields)*
+            };
+        },
+    };
+
+    let body = quote! {
+        let _nesting_guard = serdine::limits::enter_nesting()?;
+
+        #(#fields_deserialization)*
+
+        #container_assertion
+
+        #construction
+
+        #(#calc_verifications)*
+
+        #post_deserialize
+
+        Ok(result)
+    };
+
+    if let Some(remote) = &container_data.remote {
+        let remote_checks = remote_fields_checks(remote, &fields_data);
+
+        return Ok(quote!(
+            impl #type_name {
+                #vis fn deserialize<R: std::io::Read>(mut r: R) -> Result<#remote, std::io::Error> {
+                    #remote_checks
+
+                    #body
+                }
+            }
+        ));
+    }
+
+    let (trait_tokens, fn_signature) = match ctx {
+        Some(ctx) => (
+            quote! { serdine::DeserializeWithCtx<#ctx> },
+            quote! {
+                #[allow(unused_variables)]
+                fn deserialize_with_ctx<R: std::io::Read>(mut r: R, ctx: &#ctx) -> Result<Self, std::io::Error>
+            },
+        ),
+        None => (
+            quote! { serdine::Deserialize },
+            quote! { fn deserialize<R: std::io::Read>(mut r: R) -> Result<Self, std::io::Error> },
+        ),
+    };
+
+    Ok(quote!(
+        impl #trait_tokens for #type_name {
+            #fn_signature {
+                #body
+            }
+        }
+    ))
+}
+
+fn impl_trait_transparent(
+    type_name: &Ident,
+    member: &Member,
+    field_type: &Type,
+) -> syn::Result<TokenStream2> {
+    Ok(quote!(
+        impl serdine::Deserialize for #type_name {
+            fn deserialize<R: std::io::Read>(r: R) -> Result<Self, std::io::Error> {
+                <#field_type as serdine::Deserialize>::deserialize(r).map(|value| Self { #member: value })
+            }
+        }
+    ))
+}
+
+fn impl_trait_with_conversion(type_name: &Ident, stored_type: &Type) -> syn::Result<TokenStream2> {
+    Ok(quote!(
+        impl serdine::Deserialize for #type_name {
+            fn deserialize<R: std::io::Read>(r: R) -> Result<Self, std::io::Error> {
+                serdine::conversions::deserialize_as::<#stored_type, Self, R>(r)
+            }
+        }
+    ))
+}
+
+fn impl_trait_with_bitflags(type_name: &Ident, unknown_bits: &Ident) -> syn::Result<TokenStream2> {
+    Ok(quote!(
+        impl serdine::Deserialize for #type_name {
+            fn deserialize<R: std::io::Read>(r: R) -> Result<Self, std::io::Error> {
+                serdine::flags::deserialize(r, serdine::flags::UnknownBits::#unknown_bits)
+            }
+        }
+    ))
+}
+
+fn impl_trait_with_bitfield(
+    type_name: &Ident,
+    storage_type: &Ident,
+    fields_data: Vec<NamedFieldData>,
+    layouts: Vec<BitfieldLayout>,
+) -> syn::Result<TokenStream2> {
+    let fields_unpacking = fields_data.iter().zip(layouts).map(
+        |(NamedFieldData { field, .. }, BitfieldLayout { offset, width })| {
+            let field_name = field_name_tokens(field);
+            quote! { let #field = serdine::bitfield::unpack(storage, #offset, #width, #field_name)?; }
         },
     );
 
@@ -64,7 +365,9 @@ fn impl_trait_with_named_fields(
     Ok(quote!(
         impl serdine::Deserialize for #type_name {
             fn deserialize<R: std::io::Read>(mut r: R) -> Result<Self, std::io::Error> {
-                #(#fields_deserialization)*
+                let storage = <#storage_type as serdine::Deserialize>::deserialize(&mut r)? as u64;
+
+                #(#fields_unpacking)*
 
                 let result = Self {
                     #(#self_fields)*
@@ -76,6 +379,113 @@ fn impl_trait_with_named_fields(
     ))
 }
 
+fn field_deserialization(
+    NamedFieldData {
+        field_type,
+        deserialization_fn,
+        width,
+        endianness,
+        encoding,
+        bit_order,
+        presence,
+        none_value,
+        none_if_zeroed,
+        len_prefix,
+        count,
+        bool_layout,
+        strict_bool,
+        condition,
+        ctx,
+        as_type,
+        with,
+        ..
+    }: &NamedFieldData,
+    previous_fields_data: &[NamedFieldData],
+    has_container_ctx: bool,
+) -> TokenStream2 {
+    if let Some(deserialization_fn) = deserialization_fn {
+        let deserialization_fn = Ident::new(&deserialization_fn.value(), deserialization_fn.span());
+        if has_container_ctx {
+            quote! { #deserialization_fn(&mut r, ctx)? }
+        } else {
+            quote! { #deserialization_fn(&mut r)? }
+        }
+    } else if width.is_some() || endianness.is_some() {
+        let width = width_tokens(field_type, width);
+        let endianness = endianness_tokens(endianness);
+        quote! { serdine::odd_width::deserialize_int(&mut r, #width, #endianness)? }
+    } else if let Some(encoding) = encoding {
+        let deserialization_fn = format_ident!("deserialize_{}", encoding);
+        quote! { serdine::varint::#deserialization_fn(&mut r)? }
+    } else if let Some(bit_order) = bit_order {
+        quote! { serdine::bits::deserialize_bits(&mut r, serdine::BitOrder::#bit_order)? }
+    } else if let Some(presence) = presence {
+        quote! { serdine::option::deserialize_with_presence::<#presence, _, _>(&mut r)? }
+    } else if let Some(none_value) = none_value {
+        quote! { serdine::option::deserialize_with_none_value(&mut r, #none_value)? }
+    } else if *none_if_zeroed {
+        quote! { serdine::option::deserialize_if_zeroed(&mut r)? }
+    } else if let Some(condition) = condition {
+        let bindings = locals_bindings(condition, previous_fields_data);
+        let condition = value_tokens(condition);
+        quote! {
+            {
+                #bindings
+                serdine::option::deserialize_with_condition(&mut r, #condition)?
+            }
+        }
+    } else if let Some(len_prefix) = len_prefix {
+        quote! { serdine::collections::deserialize_with_prefix::<#len_prefix, _, _>(&mut r)? }
+    } else if let Some(count) = count {
+        let bindings = locals_bindings(count, previous_fields_data);
+        let count = value_tokens(count);
+        quote! {
+            {
+                #bindings
+                serdine::collections::deserialize_with_count(
+                    &mut r,
+                    serdine::collections::count_to_len(#count)?,
+                )?
+            }
+        }
+    } else if bool_layout.is_some() || *strict_bool {
+        let bool_layout = bool_layout_tokens(bool_layout);
+        quote! { serdine::boolean::deserialize(&mut r, #bool_layout, #strict_bool)? }
+    } else if let Some(ctx) = ctx {
+        let bindings = locals_bindings(ctx, previous_fields_data);
+        quote! {
+            {
+                #bindings
+                serdine::DeserializeWithCtx::deserialize_with_ctx(&mut r, &(#ctx))?
+            }
+        }
+    } else if let Some(as_type) = as_type {
+        quote! { serdine::conversions::deserialize_as::<#as_type, _, _>(&mut r)? }
+    } else if let Some(with) = with {
+        quote! { #with::deserialize(&mut r)? }
+    } else {
+        quote! { serdine::Deserialize::deserialize(&mut r)? }
+    }
+}
+
+// Name of the flag that tracks whether the field has been read; returned only if the field may be
+// not read, and it has checks (assertion or verification) to skip in that case.
+//
+fn read_flag_ident(field_data: &NamedFieldData, container_data: &ContainerData) -> Option<Ident> {
+    let has_checks = field_data.assertion.is_some() || field_data.verify;
+    let is_versioned = container_data.version.is_some()
+        && (field_data.since.is_some() || field_data.until.is_some());
+
+    if has_checks && (field_data.default.is_some() || is_versioned) {
+        Some(format_ident!(
+            "__serdine_{}_is_read",
+            field_data.field.unraw()
+        ))
+    } else {
+        None
+    }
+}
+
 fn impl_trait_with_enum_variants(
     type_name: &Ident,
     enum_repr: Ident,
@@ -90,20 +500,44 @@ fn impl_trait_with_enum_variants(
         },
     );
 
+    let bits_matches = variants_data.iter().map(
+        |VariantData {
+             variant,
+             discriminant,
+         }| {
+            quote! { #discriminant => Some(Self::#variant), }
+        },
+    );
+
+    let type_name_str = LitStr::new(&type_name.to_string(), type_name.span());
+
     Ok(quote!(
         impl serdine::Deserialize for #type_name {
             fn deserialize<R: std::io::Read>(mut r: R) -> Result<Self, std::io::Error> {
-                let mut buffer = [0; std::mem::size_of::<Self>()];
-
-                r.read_exact(&mut buffer)?;
+                let numeric_value = <#enum_repr as serdine::Deserialize>::deserialize(&mut r)?;
 
-                let result = match #enum_repr::from_le_bytes(buffer) {
+                let result = match numeric_value {
                     #(#field_matches)*
-                    value => panic!("Unrecognized value for 'MyEnum' variant: {}", value),
+                    value => {
+                        return Err(serdine::Error::UnknownVariant {
+                            type_name: #type_name_str,
+                            value: value as i128,
+                        }
+                        .into())
+                    }
                 };
 
                 Ok(result)
             }
         }
+
+        impl serdine::bitfield::FromBits for #type_name {
+            fn from_bits(bits: u64, _width: u32) -> Option<Self> {
+                match bits {
+                    #(#bits_matches)*
+                    _ => None,
+                }
+            }
+        }
     ))
 }
@@ -0,0 +1,161 @@
+use proc_macro2::{Group, Ident, Spacing, TokenTree};
+use quote::{quote, ToTokens};
+use syn::Expr;
+
+use crate::fields_data::NamedFieldData;
+
+type TokenStream2 = proc_macro2::TokenStream;
+
+// Expressions in attributes refer to the other fields by name. On serialization, they're bound as
+// references to the fields, which avoids copying them (and requiring `Clone`); on deserialization,
+// the expressions shared with serialization see the local variables as references as well (see
+// `locals_bindings()`), so that the expressions have the same semantics.
+//
+// Calculated fields are bound to their calculated value, so that the expressions are consistent
+// with the serialized data.
+//
+pub fn fields_bindings(expr: &Expr, fields_data: &[NamedFieldData]) -> TokenStream2 {
+    calculated_fields_bindings(expr, fields_data, &mut vec![])
+}
+
+// Bindings of the expression of a calculated field; a reference to the field itself is bound to the
+// stored value.
+//
+pub fn calc_bindings(field: &Ident, calc: &Expr, fields_data: &[NamedFieldData]) -> TokenStream2 {
+    calculated_fields_bindings(calc, fields_data, &mut vec![field.clone()])
+}
+
+// The fields being calculated are tracked, so that circular calculations bind the stored values,
+// instead of recursing indefinitely.
+//
+fn calculated_fields_bindings(
+    expr: &Expr,
+    fields_data: &[NamedFieldData],
+    calculating_fields: &mut Vec<Ident>,
+) -> TokenStream2 {
+    let mut referenced_idents = vec![];
+    collect_idents(expr.to_token_stream(), &mut referenced_idents);
+
+    let mut bindings = vec![];
+
+    for field_data in fields_data {
+        let NamedFieldData {
+            field,
+            field_type,
+            calc,
+            ..
+        } = field_data;
+
+        if !referenced_idents.contains(field) {
+            continue;
+        }
+
+        let binding = match calc {
+            Some(calc) if !calculating_fields.contains(field) => {
+                calculating_fields.push(field.clone());
+                let calc_bindings =
+                    calculated_fields_bindings(calc, fields_data, calculating_fields);
+                calculating_fields.pop();
+
+                quote! { let #field: &#field_type = &{ #calc_bindings #calc }; }
+            }
+            _ => {
+                let field_access = field_access_tokens(field_data);
+                quote! { let #field = &#field_access; }
+            }
+        };
+
+        bindings.push(binding);
+    }
+
+    quote! { #(#bindings)* }
+}
+
+// Bindings of the (deserialized) local variables referenced by the expression, as references.
+//
+pub fn locals_bindings(expr: &Expr, previous_fields_data: &[NamedFieldData]) -> TokenStream2 {
+    let mut referenced_idents = vec![];
+    collect_idents(expr.to_token_stream(), &mut referenced_idents);
+
+    let bindings = previous_fields_data
+        .iter()
+        .filter(|NamedFieldData { field, .. }| referenced_idents.contains(field))
+        .map(|NamedFieldData { field, .. }| quote! { let #field = &#field; });
+
+    quote! { #(#bindings)* }
+}
+
+// The result of the expression as value, whether the expression yields a value or a reference.
+//
+pub fn value_tokens(expr: &Expr) -> TokenStream2 {
+    quote! {
+        ({
+            use serdine::expressions::ExpressionValue as _;
+            (#expr).expression_value()
+        })
+    }
+}
+
+// Fields of remote types with a getter are accessed via the getter, since they may be private.
+//
+pub fn field_access_tokens(NamedFieldData { field, getter, .. }: &NamedFieldData) -> TokenStream2 {
+    match getter {
+        Some(getter) => quote! { #getter(&self) },
+        None => quote! { self.#field },
+    }
+}
+
+// Identifiers following a single dot (field accesses and method calls) are not considered; ranges
+// (`..`) are not affected.
+//
+fn collect_idents(tokens: TokenStream2, idents: &mut Vec<Ident>) {
+    let mut after_dot = false;
+    let mut after_dots = false;
+
+    for token in tokens {
+        match &token {
+            TokenTree::Ident(ident) if !after_dot || after_dots => idents.push(ident.clone()),
+            TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            _ => {}
+        }
+
+        let is_dot = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == '.');
+
+        after_dots = is_dot && after_dot;
+        after_dot = is_dot;
+    }
+}
+
+// On deserialization, `self` is not available; expressions referring to it are evaluated against
+// the constructed value instead. Only `self` used as value is replaced, not as path segment (e.g.
+// `self::helpers::checksum`).
+//
+pub fn replace_self(tokens: TokenStream2, replacement: &Ident) -> TokenStream2 {
+    let mut tokens = tokens.into_iter().peekable();
+    let mut new_tokens = vec![];
+
+    while let Some(token) = tokens.next() {
+        let new_token = match token {
+            TokenTree::Ident(ident) if ident == "self" && !is_path_separator(tokens.peek()) => {
+                TokenTree::Ident(replacement.clone())
+            }
+            TokenTree::Group(group) => {
+                let mut new_group =
+                    Group::new(group.delimiter(), replace_self(group.stream(), replacement));
+                new_group.set_span(group.span());
+                TokenTree::Group(new_group)
+            }
+            token => token,
+        };
+
+        new_tokens.push(new_token);
+    }
+
+    new_tokens.into_iter().collect()
+}
+
+// The first colon of `::` is joint to the second one.
+//
+fn is_path_separator(token: Option<&TokenTree>) -> bool {
+    matches!(token, Some(TokenTree::Punct(punct)) if punct.as_char() == ':' && punct.spacing() == Spacing::Joint)
+}
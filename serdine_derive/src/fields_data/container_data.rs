@@ -0,0 +1,30 @@
+use proc_macro2::Ident;
+use syn::{Expr, LitStr, Path, Type};
+
+#[derive(Default)]
+pub struct ContainerData {
+    // Storage type of bitfield structs.
+    pub bitfield: Option<Ident>,
+    // Variant of `serdine::BitOrder`.
+    pub bit_order: Option<Ident>,
+    // Variant of `serdine::flags::UnknownBits`.
+    pub bitflags: Option<Ident>,
+    // Context type, for `DeserializeWithCtx`/`SerializeWithCtx` implementations.
+    pub ctx: Option<Type>,
+    // Version of the format, for structs with versioned fields.
+    pub version: Option<Expr>,
+    // Assertion on the deserialized fields, and its (optional) error message.
+    pub assertion: Option<Expr>,
+    pub message: Option<LitStr>,
+    // Functions invoked after deserialization/before serialization.
+    pub post_deserialize: Option<Expr>,
+    pub pre_serialize: Option<Expr>,
+    // Intermediate types, for de/serialization via conversion.
+    pub from: Option<Type>,
+    pub try_from: Option<Type>,
+    pub into: Option<Type>,
+    // De/serialized as the single field.
+    pub transparent: bool,
+    // Foreign type mirrored by the struct; free functions are generated instead of the traits.
+    pub remote: Option<Path>,
+}
@@ -1,18 +1,88 @@
 use proc_macro2::Ident;
-use syn::{self, LitStr};
+use syn::{self, Expr, LitInt, LitStr, Path, Type};
 
 pub struct NamedFieldData {
     pub field: Ident,
+    pub field_type: Type,
     pub deserialization_fn: Option<LitStr>,
     pub serialization_fn: Option<LitStr>,
+    pub width: Option<LitInt>,
+    // Variant of `serdine::Endianness`.
+    pub endianness: Option<Ident>,
+    // Name of the encoding; matches the `serdine::varint` functions suffix.
+    pub encoding: Option<Ident>,
+    pub bits: Option<LitInt>,
+    pub align: bool,
+    // Variant of `serdine::BitOrder`; applies to bit-level types nested in byte-level ones.
+    pub bit_order: Option<Ident>,
+    // Type of the presence tag of `Option` fields.
+    pub presence: Option<Ident>,
+    pub none_value: Option<Expr>,
+    pub none_if_zeroed: bool,
+    // Type of the length prefix of collection fields.
+    pub len_prefix: Option<Ident>,
+    pub count: Option<Expr>,
+    // Variant of `serdine::boolean::BoolLayout`.
+    pub bool_layout: Option<Ident>,
+    pub strict_bool: bool,
+    // Condition of the presence of an `Option` value.
+    pub condition: Option<Expr>,
+    // Versions range (inclusive) in which the field is present.
+    pub since: Option<LitInt>,
+    pub until: Option<LitInt>,
+    // Value of the field when the input ends before it.
+    pub default: Option<Expr>,
+    // Assertion on the deserialized value, and its (optional) error message.
+    pub assertion: Option<Expr>,
+    pub message: Option<LitStr>,
+    // Value calculated on serialization, and whether it's verified on deserialization.
+    pub calc: Option<Expr>,
+    pub verify: bool,
+    // Stored type, converted from/to the field type.
+    pub as_type: Option<Type>,
+    // Type or module providing the `deserialize`/`serialize` functions (e.g. a remote mirror).
+    pub with: Option<Path>,
+    // Accessor of the field of the remote type, for private fields.
+    pub getter: Option<Path>,
+    // Context passed to the field.
+    pub ctx: Option<Expr>,
+    // Paths of the attributes set on the field, for the validations based on their presence.
+    pub attributes: Vec<Path>,
 }
 
 impl NamedFieldData {
-    pub fn new(field: Ident) -> Self {
+    pub fn new(field: Ident, field_type: Type) -> Self {
         Self {
             field,
+            field_type,
             deserialization_fn: None,
             serialization_fn: None,
+            width: None,
+            endianness: None,
+            encoding: None,
+            bits: None,
+            align: false,
+            bit_order: None,
+            presence: None,
+            none_value: None,
+            none_if_zeroed: false,
+            len_prefix: None,
+            count: None,
+            bool_layout: None,
+            strict_bool: false,
+            condition: None,
+            since: None,
+            until: None,
+            default: None,
+            assertion: None,
+            message: None,
+            calc: None,
+            verify: false,
+            as_type: None,
+            with: None,
+            getter: None,
+            ctx: None,
+            attributes: vec![],
         }
     }
 }
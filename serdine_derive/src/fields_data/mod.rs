@@ -1,5 +1,7 @@
+mod container_data;
 mod field_data;
 mod variant_data;
 
+pub use container_data::ContainerData;
 pub use field_data::NamedFieldData;
 pub use variant_data::VariantData;
@@ -0,0 +1,158 @@
+use crate::bail::bail;
+use crate::boolean::bool_layout_tokens;
+use crate::collection::{
+    collect_container_data, collect_named_fields_data, find_transparent_field,
+    find_type_numeric_repr,
+};
+use crate::fields_data::NamedFieldData;
+use crate::target::Target::ForSerialization;
+
+use proc_macro2::Ident;
+use quote::quote;
+use syn::{
+    self, parse2, Data, DataStruct, DeriveInput, Fields, GenericArgument, PathArguments, Type,
+    TypePath,
+};
+
+type TokenStream2 = proc_macro2::TokenStream;
+
+pub(crate) fn impl_fixed_size(input: impl Into<TokenStream2>) -> syn::Result<TokenStream2> {
+    let ast: DeriveInput = parse2(input.into())?;
+    let type_name = &ast.ident;
+
+    let container_data = collect_container_data(&ast)?;
+
+    // Conversions are sized as the type that is serialized.
+    //
+    let stored_type = container_data
+        .into
+        .as_ref()
+        .or(container_data.from.as_ref())
+        .or(container_data.try_from.as_ref());
+
+    let size = if container_data.transparent {
+        let (_, field_type) = find_transparent_field(&ast)?;
+        quote! { <#field_type as serdine::FixedSize>::SIZE }
+    } else if let Some(stored_type) = stored_type {
+        quote! { <#stored_type as serdine::FixedSize>::SIZE }
+    } else if container_data.bitflags.is_some() {
+        quote! { <<Self as serdine::flags::Flags>::Bits as serdine::FixedSize>::SIZE }
+    } else if let Some(storage_type) = &container_data.bitfield {
+        quote! { <#storage_type as serdine::FixedSize>::SIZE }
+    } else {
+        match &ast.data {
+            Data::Struct(DataStruct { fields, .. }) => match fields {
+                Fields::Named(fields) => {
+                    let named_fields_data = collect_named_fields_data(fields, ForSerialization)?;
+                    let fields_sizes = named_fields_data
+                        .iter()
+                        .map(field_size_tokens)
+                        .collect::<syn::Result<Vec<_>>>()?;
+
+                    quote! { 0 #(+ #fields_sizes)* }
+                }
+                Fields::Unnamed(_) => bail!("Unnamed fields not supported!"),
+                Fields::Unit => bail!("Unit fields not supported!"),
+            },
+            Data::Enum(_) => {
+                let enum_repr = find_type_numeric_repr(&ast)?;
+                quote! { <#enum_repr as serdine::FixedSize>::SIZE }
+            }
+            Data::Union(_) => bail!("Unions not supported!"),
+        }
+    };
+
+    Ok(impl_trait(type_name, size))
+}
+
+fn impl_trait(type_name: &Ident, size: TokenStream2) -> TokenStream2 {
+    quote!(
+        impl serdine::FixedSize for #type_name {
+            const SIZE: usize = #size;
+        }
+    )
+}
+
+// Fields whose size depends on their value, or on the other fields, are rejected; the types of the
+// other fields must implement `FixedSize`, which rejects the variable-length types (e.g. `Vec`).
+//
+fn field_size_tokens(field_data: &NamedFieldData) -> syn::Result<TokenStream2> {
+    let NamedFieldData {
+        field,
+        field_type,
+        serialization_fn,
+        width,
+        encoding,
+        bit_order,
+        presence,
+        none_value,
+        none_if_zeroed,
+        len_prefix,
+        count,
+        bool_layout,
+        strict_bool,
+        condition,
+        since,
+        until,
+        as_type,
+        with,
+        ..
+    } = field_data;
+
+    let variable_size_attributes = [
+        ("serialize", serialization_fn.is_some()),
+        ("encoding", encoding.is_some()),
+        ("bit_order", bit_order.is_some()),
+        ("presence", presence.is_some()),
+        ("if", condition.is_some()),
+        ("len_prefix", len_prefix.is_some()),
+        ("count", count.is_some()),
+        ("since", since.is_some()),
+        ("until", until.is_some()),
+    ];
+
+    for (attribute, is_set) in variable_size_attributes {
+        if is_set {
+            bail!(format!("Fields with the `{}` attribute don't have a fixed size", attribute) => field);
+        }
+    }
+
+    let size = if let Some(width) = width {
+        quote! { #width }
+    } else if none_value.is_some() || *none_if_zeroed {
+        // The sentinel-based encodings store the value only.
+        //
+        let inner_type = match option_inner_type(field_type) {
+            Some(inner_type) => inner_type,
+            None => bail!("The `Option` encodings require an `Option` field" => field),
+        };
+        quote! { <#inner_type as serdine::FixedSize>::SIZE }
+    } else if bool_layout.is_some() || *strict_bool {
+        let bool_layout = bool_layout_tokens(bool_layout);
+        quote! { #bool_layout.size() }
+    } else if let Some(as_type) = as_type {
+        quote! { <#as_type as serdine::FixedSize>::SIZE }
+    } else if let Some(with) = with {
+        quote! { <#with as serdine::FixedSize>::SIZE }
+    } else {
+        quote! { <#field_type as serdine::FixedSize>::SIZE }
+    };
+
+    Ok(size)
+}
+
+fn option_inner_type(field_type: &Type) -> Option<&Type> {
+    if let Type::Path(TypePath { qself: None, path }) = field_type {
+        let last_segment = path.segments.last()?;
+
+        if last_segment.ident == "Option" {
+            if let PathArguments::AngleBracketed(arguments) = &last_segment.arguments {
+                if let Some(GenericArgument::Type(inner_type)) = arguments.args.first() {
+                    return Some(inner_type);
+                }
+            }
+        }
+    }
+
+    None
+}
@@ -13,18 +13,34 @@
   clippy::type_complexity,
 )]
 
+mod assertion;
 mod bail;
+mod bit_deserialize;
+mod bit_serialize;
+mod bitfield;
+mod boolean;
 mod collection;
 mod deserialize;
+mod expressions;
 mod fields_data;
+mod fixed_size;
+mod odd_width;
+mod remote;
 mod serialize;
 mod target;
+mod version;
 
+#[cfg(test)]
+mod validation_test;
+
+use bit_deserialize::impl_bit_deserialize;
+use bit_serialize::impl_bit_serialize;
 use deserialize::impl_deserialize;
+use fixed_size::impl_fixed_size;
 use proc_macro::TokenStream;
 use serialize::impl_serialize;
 
-#[proc_macro_derive(Deserialize, attributes(deserialize))]
+#[proc_macro_derive(Deserialize, attributes(deserialize, serdine))]
 pub fn deserialize(input: TokenStream) -> TokenStream {
     let deserialize_impl = impl_deserialize(input);
 
@@ -33,7 +49,7 @@ pub fn deserialize(input: TokenStream) -> TokenStream {
         .into()
 }
 
-#[proc_macro_derive(Serialize, attributes(serialize))]
+#[proc_macro_derive(Serialize, attributes(serialize, serdine))]
 pub fn serialize(input: TokenStream) -> TokenStream {
     let serialize_impl = impl_serialize(input);
 
@@ -41,3 +57,30 @@ pub fn serialize(input: TokenStream) -> TokenStream {
         .unwrap_or_else(syn::Error::into_compile_error)
         .into()
 }
+
+#[proc_macro_derive(BitDeserialize, attributes(serdine))]
+pub fn bit_deserialize(input: TokenStream) -> TokenStream {
+    let bit_deserialize_impl = impl_bit_deserialize(input);
+
+    bit_deserialize_impl
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(BitSerialize, attributes(serdine))]
+pub fn bit_serialize(input: TokenStream) -> TokenStream {
+    let bit_serialize_impl = impl_bit_serialize(input);
+
+    bit_serialize_impl
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(FixedSize, attributes(serdine))]
+pub fn fixed_size(input: TokenStream) -> TokenStream {
+    let fixed_size_impl = impl_fixed_size(input);
+
+    fixed_size_impl
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,23 @@
+use proc_macro2::Ident;
+use quote::quote;
+use syn::{LitInt, Type};
+
+type TokenStream2 = proc_macro2::TokenStream;
+
+// When only the endianness is specified, the natural width of the type is used.
+//
+pub fn width_tokens(field_type: &Type, width: &Option<LitInt>) -> TokenStream2 {
+    match width {
+        Some(width) => quote! { #width },
+        None => quote! { <#field_type as serdine::odd_width::WidthInteger>::WIDTH },
+    }
+}
+
+// When no endianness is specified, the one of the current options is used.
+//
+pub fn endianness_tokens(endianness: &Option<Ident>) -> TokenStream2 {
+    match endianness {
+        Some(endianness) => quote! { serdine::Endianness::#endianness },
+        None => quote! { serdine::options::current_endianness() },
+    }
+}
@@ -0,0 +1,49 @@
+use proc_macro2::{Ident, Span};
+use quote::quote;
+use syn::Path;
+
+use crate::expressions::{field_access_tokens, replace_self};
+use crate::fields_data::NamedFieldData;
+
+type TokenStream2 = proc_macro2::TokenStream;
+
+// Verifies, at compile time, that the mirror fields match the remote ones, in name and type. When
+// some fields are accessed via getters, the remote type can't be constructed (its fields may be
+// private), so only the types are verified.
+//
+// The checks are closures that are never invoked; they also mark the mirror fields as read.
+//
+pub fn remote_fields_checks(remote: &Path, fields_data: &[NamedFieldData]) -> TokenStream2 {
+    let has_getters = fields_data
+        .iter()
+        .any(|field_data| field_data.getter.is_some());
+
+    if !has_getters {
+        let fields = fields_data
+            .iter()
+            .map(|NamedFieldData { field, .. }| quote! { #field: mirror.#field, });
+
+        return quote! {
+            let _ = |mirror: Self| #remote { #(#fields)* };
+        };
+    }
+
+    let remote_ident = Ident::new("remote", Span::call_site());
+
+    let type_checks = fields_data.iter().map(|field_data| {
+        let NamedFieldData {
+            field, field_type, ..
+        } = field_data;
+        let field_access = replace_self(field_access_tokens(field_data), &remote_ident);
+        quote! {
+            let _: &#field_type = &mirror.#field;
+            let _: &#field_type = &#field_access;
+        }
+    });
+
+    quote! {
+        let _ = |remote: &#remote, mirror: Self| {
+            #(#type_checks)*
+        };
+    }
+}
@@ -1,11 +1,25 @@
-use crate::collection::{collect_named_fields_data, collect_variants_data, find_type_numeric_repr};
-use crate::fields_data::NamedFieldData;
+use crate::bitfield::{
+    compute_bitfield_layouts, field_name_tokens, validate_no_bits, BitfieldLayout,
+};
+use crate::boolean::bool_layout_tokens;
+use crate::collection::{
+    collect_container_data, collect_named_fields_data, collect_variants_data,
+    find_transparent_field, find_type_numeric_repr, validate_remote_fields,
+    validate_versioned_fields,
+};
+use crate::expressions::{
+    calc_bindings, field_access_tokens, fields_bindings, replace_self, value_tokens,
+};
+use crate::fields_data::{ContainerData, NamedFieldData};
+use crate::odd_width::{endianness_tokens, width_tokens};
+use crate::remote::remote_fields_checks;
 use crate::target::Target::ForSerialization;
+use crate::version::version_condition_tokens;
 use crate::{bail::bail, fields_data::VariantData};
 
-use proc_macro2::Ident;
-use quote::quote;
-use syn::{self, parse2, Data, DataStruct, DeriveInput, Fields};
+use proc_macro2::{Ident, Span};
+use quote::{format_ident, quote};
+use syn::{self, parse2, Data, DataStruct, DeriveInput, Fields, Member, Type, Visibility};
 
 type TokenStream2 = proc_macro2::TokenStream;
 
@@ -13,21 +27,55 @@ pub(crate) fn impl_serialize(input: impl Into<TokenStream2>) -> syn::Result<Toke
     let ast: DeriveInput = parse2(input.into())?;
     let type_name = &ast.ident;
 
-    let serialize_impl = match &ast.data {
-        Data::Struct(DataStruct { fields, .. }) => match fields {
-            Fields::Named(fields) => {
-                let named_fields_data = collect_named_fields_data(fields, ForSerialization)?;
-                impl_trait_with_named_fields(type_name, named_fields_data)?
+    let container_data = collect_container_data(&ast)?;
+
+    let serialize_impl = if container_data.transparent {
+        let (member, field_type) = find_transparent_field(&ast)?;
+        impl_trait_transparent(type_name, &member, &field_type)?
+    } else if let Some(stored_type) = &container_data.into {
+        impl_trait_with_conversion(type_name, stored_type)?
+    } else if let Some(unknown_bits) = &container_data.bitflags {
+        impl_trait_with_bitflags(type_name, unknown_bits)?
+    } else {
+        match &ast.data {
+            Data::Struct(DataStruct { fields, .. }) => match fields {
+                Fields::Named(fields) => {
+                    let named_fields_data = collect_named_fields_data(fields, ForSerialization)?;
+                    validate_versioned_fields(&container_data, &named_fields_data)?;
+                    validate_remote_fields(&container_data, &named_fields_data)?;
+
+                    if let Some(storage_type) = &container_data.bitfield {
+                        let layouts = compute_bitfield_layouts(
+                            storage_type,
+                            &container_data,
+                            &named_fields_data,
+                        )?;
+                        impl_trait_with_bitfield(
+                            type_name,
+                            storage_type,
+                            named_fields_data,
+                            layouts,
+                        )?
+                    } else {
+                        validate_no_bits(&named_fields_data)?;
+                        impl_trait_with_named_fields(
+                            type_name,
+                            &ast.vis,
+                            &container_data,
+                            named_fields_data,
+                        )?
+                    }
+                }
+                Fields::Unnamed(_) => bail!("Unnamed fields not supported!"),
+                Fields::Unit => bail!("Unit fields not supported!"),
+            },
+            Data::Enum(data_enum) => {
+                let enum_repr = find_type_numeric_repr(&ast)?;
+                let variants_data = collect_variants_data(data_enum)?;
+                impl_trait_with_enum_variants(type_name, enum_repr, variants_data)?
             }
-            Fields::Unnamed(_) => bail!("Unnamed fields not supported!"),
-            Fields::Unit => bail!("Unit fields not supported!"),
-        },
-        Data::Enum(data_enum) => {
-            let enum_repr = find_type_numeric_repr(&ast)?;
-            let variants_data = collect_variants_data(data_enum)?;
-            impl_trait_with_enum_variants(type_name, enum_repr, variants_data)?
+            Data::Union(_) => bail!("Unions not supported!"),
         }
-        Data::Union(_) => bail!("Unions not supported!"),
     };
 
     Ok(quote!(
@@ -37,48 +85,299 @@ pub(crate) fn impl_serialize(input: impl Into<TokenStream2>) -> syn::Result<Toke
 
 fn impl_trait_with_named_fields(
     type_name: &Ident,
+    vis: &Visibility,
+    container_data: &ContainerData,
     fields_data: Vec<NamedFieldData>,
 ) -> syn::Result<TokenStream2> {
-    let fields_serialization = fields_data.iter().map(
-        |NamedFieldData {
-             field,
-             serialization_fn,
-             ..
-         }| {
-            if let Some(serialization_fn) = serialization_fn {
-                let serialization_fn =
-                    Ident::new(&serialization_fn.value(), serialization_fn.span());
-                quote! { #serialization_fn(&self.#field, &mut w)?; }
-            } else {
-                quote! { self.#field.serialize(&mut w)?; }
+    let ctx = container_data.ctx.as_ref();
+
+    let fields_serialization = fields_data.iter().map(|field_data| {
+        let mut quoted_serialization = field_serialization(field_data, &fields_data, ctx.is_some());
+
+        if let Some(calc) = &field_data.calc {
+            let field_type = &field_data.field_type;
+            let bindings = calc_bindings(&field_data.field, calc, &fields_data);
+            quoted_serialization = quote! {
+                {
+                    #bindings
+                    let calculated: #field_type = #calc;
+                    #quoted_serialization
+                }
+            };
+        }
+
+        // Fields not present in the version are skipped.
+        //
+        let version = container_data.version.as_ref();
+
+        match version_condition_tokens(version, field_data) {
+            Some(condition) => {
+                // A condition is returned only if the version is set.
+                //
+                let bindings = fields_bindings(version.unwrap(), &fields_data);
+                quote! {
+                    {
+                        #bindings
+                        if #condition {
+                            #quoted_serialization
+                        }
+                    }
+                }
+            }
+            None => quoted_serialization,
+        }
+    });
+
+    let fields_serialization = quote! { #(#fields_serialization)* };
+
+    // The hook receives the context, if any, like the custom serialization functions; if it returns
+    // a normalized value, the fields are serialized from it.
+    //
+    let (pre_serialize, fields_serialization) = match &container_data.pre_serialize {
+        Some(pre_serialize) => {
+            let ctx_arg = ctx.map(|_| quote! { , ctx });
+            let pre_serialize = quote! {
+                let normalized = #pre_serialize(self #ctx_arg)?;
+                let normalized = normalized.as_ref().unwrap_or(self);
+            };
+            let normalized = Ident::new("normalized", Span::call_site());
+            (
+                pre_serialize,
+                replace_self(fields_serialization, &normalized),
+            )
+        }
+        None => (quote! {}, fields_serialization),
+    };
+
+    if let Some(remote) = &container_data.remote {
+        let remote_checks = remote_fields_checks(remote, &fields_data);
+        let body = quote! {
+            #remote_checks
+
+            #pre_serialize
+
+            #fields_serialization
+
+            Ok(())
+        };
+        let body = replace_self(body, &Ident::new("remote", Span::call_site()));
+
+        return Ok(quote!(
+            impl #type_name {
+                #vis fn serialize<W: std::io::Write>(remote: &#remote, mut w: W) -> Result<(), std::io::Error> {
+                    #body
+                }
+            }
+        ));
+    }
+
+    let (trait_tokens, fn_signature) = match ctx {
+        Some(ctx) => (
+            quote! { serdine::SerializeWithCtx<#ctx> },
+            quote! {
+                #[allow(unused_variables)]
+                fn serialize_with_ctx<W: std::io::Write>(&self, mut w: W, ctx: &#ctx) -> Result<(), std::io::Error>
+            },
+        ),
+        None => (
+            quote! { serdine::Serialize },
+            quote! { fn serialize<W: std::io::Write>(&self, mut w: W) -> Result<(), std::io::Error> },
+        ),
+    };
+
+    Ok(quote!(
+        impl #trait_tokens for #type_name {
+            #fn_signature {
+                    #pre_serialize
+
+                    #fields_serialization
+
+                    Ok(())
+            }
+        }
+    ))
+}
+
+fn impl_trait_transparent(
+    type_name: &Ident,
+    member: &Member,
+    field_type: &Type,
+) -> syn::Result<TokenStream2> {
+    Ok(quote!(
+        impl serdine::Serialize for #type_name {
+            fn serialize<W: std::io::Write>(&self, w: W) -> Result<(), std::io::Error> {
+                <#field_type as serdine::Serialize>::serialize(&self.#member, w)
+            }
+        }
+    ))
+}
+
+fn impl_trait_with_conversion(type_name: &Ident, stored_type: &Type) -> syn::Result<TokenStream2> {
+    Ok(quote!(
+        impl serdine::Serialize for #type_name {
+            fn serialize<W: std::io::Write>(&self, w: W) -> Result<(), std::io::Error> {
+                serdine::conversions::serialize_as::<#stored_type, Self, W>(self, w)
+            }
+        }
+    ))
+}
+
+fn impl_trait_with_bitflags(type_name: &Ident, unknown_bits: &Ident) -> syn::Result<TokenStream2> {
+    Ok(quote!(
+        impl serdine::Serialize for #type_name {
+            fn serialize<W: std::io::Write>(&self, w: W) -> Result<(), std::io::Error> {
+                serdine::flags::serialize(self, w, serdine::flags::UnknownBits::#unknown_bits)
             }
+        }
+    ))
+}
+
+fn impl_trait_with_bitfield(
+    type_name: &Ident,
+    storage_type: &Ident,
+    fields_data: Vec<NamedFieldData>,
+    layouts: Vec<BitfieldLayout>,
+) -> syn::Result<TokenStream2> {
+    let fields_packing = fields_data.iter().zip(layouts).map(
+        |(NamedFieldData { field, .. }, BitfieldLayout { offset, width })| {
+            let field_name = field_name_tokens(field);
+            quote! { storage |= serdine::bitfield::pack(&self.#field, #offset, #width, #field_name)?; }
         },
     );
 
     Ok(quote!(
         impl serdine::Serialize for #type_name {
             fn serialize<W: std::io::Write>(&self, mut w: W) -> Result<(), std::io::Error> {
-                    #(#fields_serialization)*
+                let mut storage: u64 = 0;
 
-                    Ok(())
+                #(#fields_packing)*
+
+                (storage as #storage_type).serialize(&mut w)
             }
         }
     ))
 }
 
+fn field_serialization(
+    field_data: &NamedFieldData,
+    fields_data: &[NamedFieldData],
+    has_container_ctx: bool,
+) -> TokenStream2 {
+    let NamedFieldData {
+        field_type,
+        serialization_fn,
+        width,
+        endianness,
+        encoding,
+        bit_order,
+        presence,
+        none_value,
+        none_if_zeroed,
+        len_prefix,
+        count,
+        bool_layout,
+        strict_bool,
+        condition,
+        ctx,
+        calc,
+        as_type,
+        with,
+        ..
+    } = field_data;
+
+    // Calculated values are bound by the caller.
+    //
+    let value = match calc {
+        Some(_) => quote! { &calculated },
+        None => {
+            let field_access = field_access_tokens(field_data);
+            quote! { &#field_access }
+        }
+    };
+
+    if let Some(serialization_fn) = serialization_fn {
+        let serialization_fn = Ident::new(&serialization_fn.value(), serialization_fn.span());
+        if has_container_ctx {
+            quote! { #serialization_fn(#value, &mut w, ctx)?; }
+        } else {
+            quote! { #serialization_fn(#value, &mut w)?; }
+        }
+    } else if width.is_some() || endianness.is_some() {
+        let width = width_tokens(field_type, width);
+        let endianness = endianness_tokens(endianness);
+        quote! { serdine::odd_width::serialize_int(#value, &mut w, #width, #endianness)?; }
+    } else if let Some(encoding) = encoding {
+        let serialization_fn = format_ident!("serialize_{}", encoding);
+        quote! { serdine::varint::#serialization_fn(#value, &mut w)?; }
+    } else if let Some(bit_order) = bit_order {
+        quote! { serdine::bits::serialize_bits(#value, &mut w, serdine::BitOrder::#bit_order)?; }
+    } else if let Some(presence) = presence {
+        quote! { serdine::option::serialize_with_presence::<#presence, _, _>(#value, &mut w)?; }
+    } else if let Some(none_value) = none_value {
+        quote! { serdine::option::serialize_with_none_value(#value, &mut w, #none_value)?; }
+    } else if *none_if_zeroed {
+        quote! { serdine::option::serialize_if_zeroed(#value, &mut w)?; }
+    } else if let Some(condition) = condition {
+        let bindings = fields_bindings(condition, fields_data);
+        let condition = value_tokens(condition);
+        quote! {
+            {
+                #bindings
+                serdine::option::serialize_with_condition(#value, &mut w, #condition)?;
+            }
+        }
+    } else if let Some(len_prefix) = len_prefix {
+        quote! { serdine::collections::serialize_with_prefix::<#len_prefix, _, _>(#value, &mut w)?; }
+    } else if let Some(count) = count {
+        let bindings = fields_bindings(count, fields_data);
+        let count = value_tokens(count);
+        quote! {
+            {
+                #bindings
+                serdine::collections::serialize_with_count(
+                    #value,
+                    &mut w,
+                    serdine::collections::count_to_len(#count)?,
+                )?;
+            }
+        }
+    } else if bool_layout.is_some() || *strict_bool {
+        let bool_layout = bool_layout_tokens(bool_layout);
+        quote! { serdine::boolean::serialize(#value, &mut w, #bool_layout)?; }
+    } else if let Some(ctx) = ctx {
+        let bindings = fields_bindings(ctx, fields_data);
+        quote! {
+            {
+                #bindings
+                serdine::SerializeWithCtx::serialize_with_ctx(#value, &mut w, &(#ctx))?;
+            }
+        }
+    } else if let Some(as_type) = as_type {
+        quote! { serdine::conversions::serialize_as::<#as_type, _, _>(#value, &mut w)?; }
+    } else if let Some(with) = with {
+        quote! { #with::serialize(#value, &mut w)?; }
+    } else {
+        quote! { (#value).serialize(&mut w)?; }
+    }
+}
+
 fn impl_trait_with_enum_variants(
     type_name: &Ident,
     enum_repr: Ident,
     variants_data: Vec<VariantData>,
 ) -> syn::Result<TokenStream2> {
-    let field_matches = variants_data.iter().map(
-        |VariantData {
-             variant,
-             discriminant,
-         }| {
-            quote! { Self::#variant => #discriminant, }
-        },
-    );
+    let field_matches = variants_data
+        .iter()
+        .map(
+            |VariantData {
+                 variant,
+                 discriminant,
+             }| {
+                quote! { Self::#variant => #discriminant, }
+            },
+        )
+        // Shared between the trait implementations.
+        .collect::<Vec<_>>();
 
     Ok(quote!(
         impl serdine::Serialize for #type_name {
@@ -92,5 +391,19 @@ fn impl_trait_with_enum_variants(
                 Ok(())
             }
         }
+
+        impl serdine::bitfield::ToBits for #type_name {
+            fn to_bits(&self, width: u32) -> Option<u64> {
+                let bits: u64 = match self {
+                    #(#field_matches)*
+                };
+
+                if width == 64 || bits >> width == 0 {
+                    Some(bits)
+                } else {
+                    None
+                }
+            }
+        }
     ))
 }
@@ -0,0 +1,161 @@
+use crate::bit_deserialize::impl_bit_deserialize;
+use crate::bit_serialize::impl_bit_serialize;
+use crate::deserialize::impl_deserialize;
+use crate::serialize::impl_serialize;
+
+type TokenStream2 = proc_macro2::TokenStream;
+
+// Returns the error message of the derive of the given source.
+//
+fn derive_error<F>(impl_derive: F, source: &str) -> String
+where
+    F: Fn(TokenStream2) -> syn::Result<TokenStream2>,
+{
+    let input = source.parse::<TokenStream2>().unwrap();
+
+    impl_derive(input).err().unwrap().to_string()
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// BIT-LEVEL FIELDS
+// ////////////////////////////////////////////////////////////////////////////////
+
+const BYTE_LEVEL_ATTRIBUTES: &[&str] = &[
+    r#"presence = "u8""#,
+    "none_value = 1",
+    "none_if_zeroed",
+    r#"len_prefix = "u8""#,
+    r#"count = "1""#,
+    r#"bool_layout = "u8""#,
+    "strict_bool",
+    r#"ctx = "1""#,
+    r#"if = "true""#,
+    "default",
+    r#"as = "u16""#,
+];
+
+fn assert_bit_level_attributes_rejected(attributes: &[&str]) {
+    for attribute in attributes {
+        let source = format!(
+            "pub struct MyBitLevelStruct {{ #[serdine({})] pub my_u8: u8 }}",
+            attribute
+        );
+
+        for impl_derive in [impl_bit_deserialize, impl_bit_serialize] {
+            assert_eq!(
+                "Bit-level fields support only the `bits` and `align` attributes",
+                derive_error(impl_derive, &source),
+                "{}",
+                attribute
+            );
+        }
+
+        // Some attributes are already rejected in combination with `bits`, with a different error.
+        //
+        let source = format!(
+            r#"#[serdine(bitfield = "u8")] pub struct MyBitfield {{ #[serdine(bits = 3, {})] pub my_u8: u8 }}"#,
+            attribute
+        );
+
+        for impl_derive in [impl_deserialize, impl_serialize] {
+            assert!(
+                !derive_error(impl_derive, &source).is_empty(),
+                "{}",
+                attribute
+            );
+        }
+    }
+}
+
+#[test]
+fn test_bit_level_fields_byte_level_attributes() {
+    assert_bit_level_attributes_rejected(BYTE_LEVEL_ATTRIBUTES);
+}
+
+// Checks are not supported at bit level.
+//
+#[test]
+fn test_bit_level_fields_assertions() {
+    assert_bit_level_attributes_rejected(&[r#"assert = "*my_u8 < 2""#]);
+
+    let source = r#"
+        #[serdine(bitfield = "u8")]
+        pub struct MyBitfield {
+            #[serdine(bits = 3, assert = "my_u8 < 2", message = "Out of range")]
+            pub my_u8: u8,
+        }
+    "#;
+
+    for impl_derive in [impl_deserialize, impl_serialize] {
+        assert_eq!(
+            "Bitfield fields support only the `bits` attribute",
+            derive_error(impl_derive, source)
+        );
+    }
+}
+
+// Calculated values are not supported at bit level.
+//
+#[test]
+fn test_bit_level_fields_calculated_values() {
+    assert_bit_level_attributes_rejected(&[r#"calc = "1", verify"#]);
+
+    let source = r#"
+        #[serdine(bitfield = "u8")]
+        pub struct MyBitfield {
+            #[serdine(bits = 3, calc = "1", verify)]
+            pub my_u8: u8,
+        }
+    "#;
+
+    for impl_derive in [impl_deserialize, impl_serialize] {
+        assert_eq!(
+            "Bitfield fields support only the `bits` attribute",
+            derive_error(impl_derive, source)
+        );
+    }
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+// CONVERSIONS
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn test_conversion_with_struct_attributes() {
+    let source = r#"
+        #[serdine(from = "u32", assert = "true")]
+        pub struct MyConvertedStruct {
+            pub my_u32: u32,
+        }
+    "#;
+
+    assert_eq!(
+        "The `from`/`try_from`/`into` attributes can't be combined with `ctx`, `version`, `assert`, `post_deserialize` and `pre_serialize`",
+        derive_error(impl_deserialize, source)
+    );
+}
+
+#[test]
+fn test_conversion_with_field_attributes() {
+    for container_attribute in [r#"from = "u32""#, r#"try_from = "u32""#, r#"into = "u32""#] {
+        let source = format!(
+            r#"
+                #[serdine({})]
+                pub struct MyConvertedStruct {{
+                    #[serdine(width = 3)]
+                    pub my_u32: u32,
+                }}
+            "#,
+            container_attribute
+        );
+
+        for impl_derive in [impl_deserialize, impl_serialize] {
+            assert_eq!(
+                "The fields of a struct with `from`/`try_from`/`into` can't have attributes",
+                derive_error(impl_derive, &source),
+                "{}",
+                container_attribute
+            );
+        }
+    }
+}
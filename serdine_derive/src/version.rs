@@ -0,0 +1,26 @@
+use quote::quote;
+use syn::Expr;
+
+use crate::expressions::value_tokens;
+use crate::fields_data::NamedFieldData;
+
+type TokenStream2 = proc_macro2::TokenStream;
+
+// Returns the condition of the presence of a field, if it's versioned; the range is inclusive. The
+// fields referenced by the version expression must be bound by the caller.
+//
+pub fn version_condition_tokens(
+    version: Option<&Expr>,
+    field_data: &NamedFieldData,
+) -> Option<TokenStream2> {
+    let version = value_tokens(version?);
+
+    let condition = match (&field_data.since, &field_data.until) {
+        (Some(since), Some(until)) => quote! { (#since..=#until).contains(&#version) },
+        (Some(since), None) => quote! { #version >= #since },
+        (None, Some(until)) => quote! { #version <= #until },
+        (None, None) => return None,
+    };
+
+    Some(condition)
+}